@@ -215,6 +215,7 @@ impl Display for Temple {
             (Temple::BEAST, "beast"),
             (Temple::UNDEAD, "undead"),
             (Temple::TECH, "tech"),
+            (Temple::MAGICK, "magick"),
             (Temple::FOOL, "fool"),
             (Temple::ARTISTRY, "artistry"),
         ];
@@ -230,7 +231,7 @@ impl Display for Temple {
 }
 
 /// Enum for the diffrent attack type.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Attack {
     /// Numeric attack value.
     Num(isize),
@@ -543,4 +544,57 @@ impl Traits {
             flags: TraitsFlag::empty(),
         }
     }
+
+    /// Return `true` if this traits have no flags and no string traits.
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// assert!(Traits::with_flags(TraitsFlag::empty()).is_empty());
+    /// assert!(Traits::with_string(vec![]).is_empty());
+    /// assert!(!Traits::with_flags(TraitsFlag::HARD).is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty() && self.strings.as_ref().is_none_or(Vec::is_empty)
+    }
+
+    /// Return `true` if this traits contain every flags and string traits in `other`.
+    ///
+    /// String traits are compare case-insensitively.
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// let traits = Traits {
+    ///     strings: Some(vec!["Full Art".to_string(), "Token".to_string()]),
+    ///     flags: TraitsFlag::CONDUCTIVE | TraitsFlag::HARD,
+    /// };
+    ///
+    /// assert!(traits.contains(&Traits::with_flags(TraitsFlag::CONDUCTIVE)));
+    /// assert!(traits.contains(&Traits::with_string(vec!["full art".to_string()])));
+    /// assert!(!traits.contains(&Traits::with_flags(TraitsFlag::BAN)));
+    /// assert!(!traits.contains(&Traits::with_string(vec!["Tutor".to_string()])));
+    /// ```
+    #[must_use]
+    pub fn contains(&self, other: &Traits) -> bool {
+        if !self.flags.contains(other.flags) {
+            return false;
+        }
+
+        let Some(other) = &other.strings else {
+            return true;
+        };
+
+        let strings = self
+            .strings
+            .iter()
+            .flatten()
+            .map(|s| s.to_lowercase())
+            .collect::<Vec<_>>();
+
+        other.iter().all(|s| strings.contains(&s.to_lowercase()))
+    }
 }
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use crate::query::normalize_name;
+use crate::{Card, Mox, Rarity, Temple};
+
+/// Inverted indexes for a [`Set`](crate::Set) to speed up querying.
+///
+/// The index map card properties to the position of the cards in [`Set::cards`](crate::Set::cards)
+/// so [`QueryBuilder`](crate::query::QueryBuilder) only have to look at card that could match
+/// instead of every card in the set. Positions are always sorted.
+///
+/// The index is only valid for the cards it was built from, if you change the cards of a set you
+/// have to rebuild it with [`Set::build_index`](crate::Set::build_index). Set fetched with the
+/// fetch functions already have their index built.
+#[derive(Debug, Clone, Default)]
+pub struct SetIndex {
+    len: usize,
+
+    names: Vec<String>,
+    sigils: HashMap<String, Vec<usize>>,
+    tribes: HashMap<String, Vec<usize>>,
+    tribeless: Vec<usize>,
+
+    rarities: [Vec<usize>; 5],
+    temples: Vec<(Temple, Vec<usize>)>,
+
+    blood: HashMap<isize, Vec<usize>>,
+    bone: HashMap<isize, Vec<usize>>,
+    energy: HashMap<isize, Vec<usize>>,
+    mox: Vec<(Mox, Vec<usize>)>,
+    costless: Vec<usize>,
+}
+
+impl SetIndex {
+    /// Build the index for a list of cards.
+    #[must_use]
+    pub fn new<E, C>(cards: &[Card<E, C>]) -> Self
+    where
+        E: Clone,
+        C: Clone + PartialEq,
+    {
+        let mut index = SetIndex {
+            len: cards.len(),
+            names: cards.iter().map(|c| normalize_name(&c.name)).collect(),
+            temples: Temple::all().iter().map(|t| (t, vec![])).collect(),
+            mox: Mox::all().iter().map(|m| (m, vec![])).collect(),
+            ..Default::default()
+        };
+
+        for (i, card) in cards.iter().enumerate() {
+            for sigil in &card.sigils {
+                push(index.sigils.entry(sigil.to_lowercase()).or_default(), i);
+            }
+
+            match &card.tribes {
+                Some(tribes) => {
+                    for word in tribes.to_lowercase().split_whitespace() {
+                        push(index.tribes.entry(word.to_owned()).or_default(), i);
+                    }
+                }
+                None => index.tribeless.push(i),
+            }
+
+            index.rarities[rarity_pos(&card.rarity)].push(i);
+
+            for (t, pos) in &mut index.temples {
+                if card.temple.contains(*t) {
+                    pos.push(i);
+                }
+            }
+
+            match &card.costs {
+                Some(costs) => {
+                    index.blood.entry(costs.blood).or_default().push(i);
+                    index.bone.entry(costs.bone).or_default().push(i);
+                    index.energy.entry(costs.energy).or_default().push(i);
+
+                    for (m, pos) in &mut index.mox {
+                        if costs.mox.contains(*m) {
+                            pos.push(i);
+                        }
+                    }
+                }
+                None => index.costless.push(i),
+            }
+        }
+
+        index
+    }
+
+    /// The number of cards this index was built for.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the index was built for no card.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The normalized name of every card in the same order as the cards, see
+    /// [`normalize_name`].
+    ///
+    /// Useful for fuzzy matching so the names don't have to be normalize on every search.
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Position of cards with a sigil that contain the given lowercase text.
+    #[must_use]
+    pub fn sigil(&self, text: &str) -> Vec<usize> {
+        union_all(
+            self.sigils
+                .iter()
+                .filter(|(s, _)| s.contains(text))
+                .map(|(_, pos)| pos),
+        )
+    }
+
+    /// Position of cards with a tribe that contain the given lowercase text.
+    ///
+    /// Tribes are index by word so this return [`None`] if the text is empty or contain
+    /// whitespace.
+    #[must_use]
+    pub fn tribe(&self, text: &str) -> Option<Vec<usize>> {
+        if text.is_empty() || text.contains(char::is_whitespace) {
+            return None;
+        }
+
+        Some(union_all(
+            self.tribes
+                .iter()
+                .filter(|(t, _)| t.contains(text))
+                .map(|(_, pos)| pos),
+        ))
+    }
+
+    /// Position of cards with no tribe.
+    #[must_use]
+    pub fn tribeless(&self) -> &[usize] {
+        &self.tribeless
+    }
+
+    /// Position of cards with the given rarity.
+    #[must_use]
+    pub fn rarity(&self, rarity: &Rarity) -> &[usize] {
+        &self.rarities[rarity_pos(rarity)]
+    }
+
+    /// Position of cards that belong to every temple in the given flags.
+    #[must_use]
+    pub fn temple(&self, temple: Temple) -> Vec<usize> {
+        self.temples
+            .iter()
+            .filter(|(t, _)| temple.contains(*t))
+            .map(|(_, pos)| pos.clone())
+            .reduce(|a, b| intersect(&a, &b))
+            .unwrap_or_else(|| (0..self.len).collect())
+    }
+
+    /// Position of cards with exactly the given blood cost.
+    #[must_use]
+    pub fn blood(&self, count: isize) -> &[usize] {
+        self.blood.get(&count).map_or(&[], Vec::as_slice)
+    }
+
+    /// Position of cards with exactly the given bone cost.
+    #[must_use]
+    pub fn bone(&self, count: isize) -> &[usize] {
+        self.bone.get(&count).map_or(&[], Vec::as_slice)
+    }
+
+    /// Position of cards with exactly the given energy cost.
+    #[must_use]
+    pub fn energy(&self, count: isize) -> &[usize] {
+        self.energy.get(&count).map_or(&[], Vec::as_slice)
+    }
+
+    /// Position of cards that cost every mox in the given flags. Cards with no cost are not
+    /// include.
+    #[must_use]
+    pub fn mox(&self, mox: Mox) -> Vec<usize> {
+        self.mox
+            .iter()
+            .filter(|(m, _)| mox.contains(*m))
+            .map(|(_, pos)| pos.clone())
+            .reduce(|a, b| intersect(&a, &b))
+            .unwrap_or_else(|| {
+                // every card with a cost
+                let costless = &self.costless;
+                (0..self.len)
+                    .filter(|i| costless.binary_search(i).is_err())
+                    .collect()
+            })
+    }
+
+    /// Position of cards with no cost.
+    #[must_use]
+    pub fn costless(&self) -> &[usize] {
+        &self.costless
+    }
+}
+
+/// Push a position if it was not just push, card can have the same sigil twice.
+fn push(pos: &mut Vec<usize>, i: usize) {
+    if pos.last() != Some(&i) {
+        pos.push(i);
+    }
+}
+
+fn rarity_pos(rarity: &Rarity) -> usize {
+    match rarity {
+        Rarity::SIDE => 0,
+        Rarity::COMMON => 1,
+        Rarity::UNCOMMON => 2,
+        Rarity::RARE => 3,
+        Rarity::UNIQUE => 4,
+    }
+}
+
+/// Intersect 2 sorted list of positions.
+pub(crate) fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut out = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Union 2 sorted list of positions.
+pub(crate) fn union(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut out: Vec<usize> = a.iter().chain(b).copied().collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+fn union_all<'a>(lists: impl Iterator<Item = &'a Vec<usize>>) -> Vec<usize> {
+    let mut out: Vec<usize> = lists.flatten().copied().collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
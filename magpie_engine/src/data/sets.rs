@@ -1,7 +1,10 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::query::normalize_name;
 use crate::Card;
+use crate::SetIndex;
+use crate::Sigil;
 use crate::UpgradeCard;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -30,7 +33,7 @@ use std::fmt::Display;
 /// assert!(SetCode::new("🤓💀🧏").is_none()); // Invalid because it not ascii
 /// assert!(SetCode::new(";;;").is_none()); // These are actually greek question mark
 /// ```
-#[derive(Clone, Copy, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SetCode([u8; 3]);
 
 impl SetCode {
@@ -79,6 +82,22 @@ impl SetCode {
     pub fn bytes(&self) -> [u8; 3] {
         self.0
     }
+
+    /// Compare 2 set code ignoring case, like [`str::eq_ignore_ascii_case`].
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// let aug = SetCode::new("Aug").unwrap();
+    ///
+    /// assert!(aug.eq_ignore_ascii_case(&SetCode::new("aug").unwrap()));
+    /// assert!(!aug.eq_ignore_ascii_case(&SetCode::new("std").unwrap()));
+    /// ```
+    #[must_use]
+    pub fn eq_ignore_ascii_case(&self, other: &SetCode) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
 }
 
 impl From<SetCode> for String {
@@ -117,11 +136,28 @@ where
     ///
     /// These cards should be shared along with the card in the pools to save space on larger set.
     pub cards: Vec<Card<E, C>>,
-    /// The sigils description look up table for the set.
+    /// The sigils look up table for the set, map the sigil name to the sigil.
     ///
     /// Set are require to include **every** sigil in this look up table. So you can safely get
-    /// value from this table without worrying about [`None`].
-    pub sigils_description: HashMap<String, String>,
+    /// value from this table without worrying about [`None`]. Sigil that are not defined by the
+    /// set use the [`UNDEFINED_SIGIL`](crate::UNDEFINED_SIGIL) placeholder.
+    pub sigils: HashMap<String, Sigil>,
+    /// The traits description look up table for the set, map the trait name to its description.
+    ///
+    /// Unlike sigils not every trait have a description so this can be missing some trait.
+    #[serde(default)]
+    pub traits_description: HashMap<String, String>,
+    /// The index use to speed up querying the set.
+    ///
+    /// The index is not serialize, use [`Set::build_index`] to build it after loading a set.
+    #[serde(skip)]
+    pub index: Option<SetIndex>,
+    /// Alias look up table for the set, map a normalized alias to a card name.
+    ///
+    /// Aliases are community shorthand like `mole` for Mole Man, use [`Set::add_alias`] to add
+    /// them so they are normalized correctly.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
 }
 
 impl<T, U> Set<T, U>
@@ -151,7 +187,70 @@ where
             code: self.code,
             name: self.name,
             cards: self.cards.into_iter().map(UpgradeCard::upgrade).collect(),
-            sigils_description: self.sigils_description,
+            sigils: self.sigils,
+            traits_description: self.traits_description,
+            // upgrading don't change the cards order so the index is still valid
+            index: self.index,
+            aliases: self.aliases,
         }
     }
+
+    /// Build or rebuild the [`SetIndex`] for this set.
+    ///
+    /// You have to call this again if you change the set cards.
+    pub fn build_index(&mut self) {
+        self.index = Some(SetIndex::new(&self.cards));
+    }
+
+    /// Add an alias for a card, the alias is normalized with [`normalize_name`] so case and
+    /// punctuation doesn't matter. Return the previous card name if the alias was already use.
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// let mut set: Set<(), ()> = Set {
+    ///     code: SetCode::new("abc").unwrap(),
+    ///     name: String::from("Example"),
+    ///     cards: vec![],
+    ///     sigils: Default::default(),
+    ///     traits_description: Default::default(),
+    ///     index: None,
+    ///     aliases: Default::default(),
+    /// };
+    ///
+    /// set.add_alias("Mole!", "Mole Man");
+    /// assert_eq!(set.alias("mole"), Some("Mole Man"));
+    /// assert_eq!(set.alias("mole man"), None);
+    /// ```
+    ///
+    /// [`normalize_name`]: crate::query::normalize_name
+    pub fn add_alias(&mut self, alias: &str, name: impl Into<String>) -> Option<String> {
+        self.aliases.insert(normalize_name(alias), name.into())
+    }
+
+    /// Get the card name an alias refer to.
+    #[must_use]
+    pub fn alias(&self, alias: &str) -> Option<&str> {
+        self.aliases.get(&normalize_name(alias)).map(String::as_str)
+    }
+
+    /// Get a sigil from the sigils look up table.
+    #[must_use]
+    pub fn sigil(&self, name: &str) -> Option<&Sigil> {
+        self.sigils.get(name)
+    }
+
+    /// Get the description of a trait if the set have one.
+    #[must_use]
+    pub fn trait_description(&self, name: &str) -> Option<&str> {
+        self.traits_description.get(name).map(String::as_str)
+    }
+
+    /// Build the [`SetIndex`] for this set and return it.
+    #[must_use]
+    pub fn indexed(mut self) -> Self {
+        self.build_index();
+        self
+    }
 }
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::SetCode;
+
+/// The name of the placeholder sigil for sigil that are not defined by the set.
+pub const UNDEFINED_SIGIL: &str = "UNDEFINED SIGIL";
+
+/// The kind of a sigil.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SigilCategory {
+    /// Sigil you have to activate by paying something.
+    Activated,
+    /// Sigil that work with circuits and conduits.
+    Conduit,
+    /// Sigil that always work on their own, this is the default.
+    #[default]
+    Passive,
+    /// Not really a sigil but a trait that is list with the sigils.
+    Trait,
+}
+
+impl SigilCategory {
+    /// Guess the category of a sigil from its description.
+    ///
+    /// Most set don't tell us the category so we have to guess. Nothing can be guess as a
+    /// [`SigilCategory::Trait`], the fetcher have to set it themself.
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// assert_eq!(SigilCategory::guess("Activated (2 bones): Gain 1 power."), SigilCategory::Activated);
+    /// assert_eq!(SigilCategory::guess("If this card is part of a circuit, it gain 1 power."), SigilCategory::Conduit);
+    /// assert_eq!(SigilCategory::guess("This card flies over opposing cards."), SigilCategory::Passive);
+    /// ```
+    #[must_use]
+    pub fn guess(description: &str) -> Self {
+        let lower = description.to_lowercase();
+
+        if lower.starts_with("activate") || lower.contains("activated:") {
+            SigilCategory::Activated
+        } else if lower.contains("conduit") || lower.contains("circuit") {
+            SigilCategory::Conduit
+        } else {
+            SigilCategory::Passive
+        }
+    }
+}
+
+impl Display for SigilCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SigilCategory::Activated => "Activated",
+                SigilCategory::Conduit => "Conduit",
+                SigilCategory::Passive => "Passive",
+                SigilCategory::Trait => "Trait",
+            }
+        )
+    }
+}
+
+/// A sigil and its infomation.
+///
+/// Cards only store their sigils name, you can get the full sigil with [`Set::sigil`].
+///
+/// [`Set::sigil`]: crate::Set::sigil
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sigil {
+    /// The name of the sigil.
+    pub name: String,
+    /// What the sigil do.
+    pub description: String,
+    /// Url to the sigil icon if the set have one.
+    pub icon: Option<String>,
+    /// The kind of sigil.
+    pub category: SigilCategory,
+    /// The set this sigil come from.
+    pub set: SetCode,
+}
+
+impl Sigil {
+    /// Create a new sigil with no icon, the category is guess from the description with
+    /// [`SigilCategory::guess`].
+    #[must_use]
+    pub fn new(name: impl Into<String>, description: impl Into<String>, set: SetCode) -> Self {
+        let description = description.into();
+        Sigil {
+            name: name.into(),
+            category: SigilCategory::guess(&description),
+            description,
+            icon: None,
+            set,
+        }
+    }
+
+    /// The placeholder sigil use for sigil that are not defined by the set.
+    #[must_use]
+    pub fn undefined(set: SetCode) -> Self {
+        Sigil::new(UNDEFINED_SIGIL, "THIS SIGIL IS NOT DEFINED BY THE SET", set)
+    }
+}
@@ -1,9 +1,23 @@
 //! Contain type for extension of sets
 
+/// Trait for [`Card::extra`](crate::Card::extra) extensions that expose their fields by name so
+/// they can be query using [`FieldFilter`](crate::query::FieldFilter).
+pub trait ExtFields {
+    /// Get a field value by name. Return [`None`] if the extension don't have that field.
+    fn field(&self, name: &str) -> Option<&str>;
+}
+
+impl ExtFields for () {
+    fn field(&self, _: &str) -> Option<&str> {
+        None
+    }
+}
+
 pub mod aug {
     //! Extension type for Augmented
     use serde::{Deserialize, Serialize};
 
+    use super::ExtFields;
     use crate::{self_upgrade, Card, MoxCount};
 
     /// Augmented's [`Card`] extensions.
@@ -22,6 +36,15 @@ pub mod aug {
         pub max: isize,
     }
 
+    impl ExtFields for AugExt {
+        fn field(&self, name: &str) -> Option<&str> {
+            match name {
+                "artist" => Some(&self.artist),
+                _ => None,
+            }
+        }
+    }
+
     self_upgrade!(AugExt, AugCosts);
 
     /// Type alias for a Augmented [`Card`]
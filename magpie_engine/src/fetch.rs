@@ -2,6 +2,7 @@
 
 use isahc::ReadResponseExt;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Display;
 
@@ -15,7 +16,7 @@ pub use cti::*;
 pub use desc::*;
 pub use imf::*;
 
-use crate::Set;
+use crate::{Card, Set, Sigil, SigilCategory};
 
 /// Type alias for set fetch output.
 pub type SetResult<E, C> = Result<Set<E, C>, SetError>;
@@ -40,6 +41,34 @@ impl Display for FetchError {
 
 impl Error for FetchError {}
 
+/// Get the description of every trait the cards use from the sigils sheet.
+///
+/// Some set list their traits in the same sheet as the sigils, the rows for traits are mark as
+/// [`SigilCategory::Trait`]. Traits with no row in the sheet are left out.
+fn traits_description<E, C>(
+    cards: &[Card<E, C>],
+    sigils: &mut HashMap<String, Sigil>,
+) -> HashMap<String, String>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    let mut out = HashMap::new();
+
+    for t in cards
+        .iter()
+        .filter_map(|c| c.traits.as_ref()?.strings.as_ref())
+        .flatten()
+    {
+        if let Some(s) = sigils.get_mut(t) {
+            s.category = SigilCategory::Trait;
+            out.insert(t.clone(), s.description.clone());
+        }
+    }
+
+    out
+}
+
 /// Just a wrapper around [`isahc`](https://docs.rs/isahc) to fetch and parse json.
 /// # Example
 /// ```rust
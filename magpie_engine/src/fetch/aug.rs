@@ -9,10 +9,11 @@ use serde::Deserialize;
 use crate::{
     ext::aug::{AugCosts, AugExt},
     fetch::fetch_json,
-    Attack, Card, Costs, Mox, MoxCount, Rarity, Set, SetCode, Temple, Traits, TraitsFlag,
+    Attack, Card, Costs, Mox, MoxCount, Rarity, Set, SetCode, Sigil, Temple, Traits, TraitsFlag,
+    UNDEFINED_SIGIL,
 };
 
-use super::{SetError, SetResult};
+use super::{traits_description, SetError, SetResult};
 
 /// The branches of Augmented
 pub enum AugBranch {
@@ -34,24 +35,22 @@ pub fn fetch_aug_set(branch: AugBranch, code: SetCode) -> SetResult<AugExt, AugC
 
     let card_url = format!("https://opensheet.elk.sh/{sheet_id}/2");
     let raw_card: Vec<AugCard> =
-        fetch_json(&card_url).map_err(|e| SetError::FetchError(e, card_url.to_string()))?;
+        fetch_json(&card_url).map_err(|e| SetError::FetchError(e, card_url.clone()))?;
 
     let sigil_url = format!("https://opensheet.elk.sh/{sheet_id}/3");
     let sigil: Vec<AugSigil> =
-        fetch_json(&sigil_url).map_err(|e| SetError::FetchError(e, sigil_url.to_string()))?;
+        fetch_json(&sigil_url).map_err(|e| SetError::FetchError(e, sigil_url.clone()))?;
 
     let mut cards = Vec::with_capacity(raw_card.len());
 
-    let mut sigils_description = HashMap::with_capacity(sigil.len());
+    let mut sigils = HashMap::with_capacity(sigil.len());
 
     for s in sigil {
-        sigils_description.insert(s.name, s.text.replace('\n', ""));
+        let sigil = Sigil::new(s.name.clone(), s.text.replace('\n', ""), code);
+        sigils.insert(s.name, sigil);
     }
 
-    sigils_description.insert(
-        String::from("UNDEFINED SIGIL"),
-        "THIS SIGIL IS NOT DEFINED BY THE SET".to_owned(),
-    );
+    sigils.insert(UNDEFINED_SIGIL.to_owned(), Sigil::undefined(code));
 
     for card in raw_card {
         let costs;
@@ -209,10 +208,10 @@ pub fn fetch_aug_set(branch: AugBranch, code: SetCode) -> SetResult<AugExt, AugC
             } else {
                 card.sigils.split(", ").map(|s| {
                     let s = s.to_owned();
-                    if sigils_description.contains_key(&s) {
+                    if sigils.contains_key(&s) {
                         s
                     } else {
-                        String::from("UNDEFINED SIGIL")
+                        UNDEFINED_SIGIL.to_owned()
                     }
                 }).collect()
             },
@@ -244,12 +243,18 @@ pub fn fetch_aug_set(branch: AugBranch, code: SetCode) -> SetResult<AugExt, AugC
         cards.push(card);
     }
 
+    let traits_description = traits_description(&cards, &mut sigils);
+
     Ok(Set {
         code,
         name: String::from("Augmented"),
         cards,
-        sigils_description,
-    })
+        sigils,
+        traits_description,
+        index: None,
+        aliases: HashMap::new(),
+    }
+    .indexed())
 }
 
 /// Json scheme for aug card.
@@ -6,9 +6,12 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
-use crate::{fetch::fetch_json, Attack, Card, Costs, Mox, MoxCount, Rarity, Set, SetCode, Temple};
+use crate::{
+    fetch::fetch_json, Attack, Card, Costs, Mox, MoxCount, Rarity, Set, SetCode, Sigil, Temple,
+    UNDEFINED_SIGIL,
+};
 
-use super::{SetError, SetResult};
+use super::{traits_description, SetError, SetResult};
 
 /// Fetch Custom TCG Inscryption from the
 /// [sheet](https://docs.google.com/spreadsheets/d/152SuTx1fVc4zsqL4_zVDPx69sd9vYWikc2Ce9Y5vhJE/edit?gid=0#gid=0).
@@ -24,16 +27,14 @@ pub fn fetch_cti_set(code: SetCode) -> SetResult<(), ()> {
 
     let mut cards = Vec::with_capacity(raw_card.len());
 
-    let mut sigils_description = HashMap::with_capacity(sigil.len());
+    let mut sigils = HashMap::with_capacity(sigil.len());
 
     for s in sigil {
-        sigils_description.insert(s.name, s.text.replace('\n', ""));
+        let sigil = Sigil::new(s.name.clone(), s.text.replace('\n', ""), code);
+        sigils.insert(s.name, sigil);
     }
 
-    sigils_description.insert(
-        String::from("UNDEFINED SIGIL"),
-        "THIS SIGIL IS NOT DEFINED BY THE SET".to_owned(),
-    );
+    sigils.insert(UNDEFINED_SIGIL.to_owned(), Sigil::undefined(code));
 
     for card in raw_card {
         let costs;
@@ -135,8 +136,8 @@ pub fn fetch_cti_set(code: SetCode) -> SetResult<(), ()> {
                 .filter(|s| !s.is_empty())
                 .map(
                     |s|
-                    if sigils_description.contains_key(&s) { s }
-                    else { String::from("UNDEFINED SIGIL") }
+                    if sigils.contains_key(&s) { s }
+                    else { UNDEFINED_SIGIL.to_owned() }
                 )
                 .collect(),
 
@@ -153,12 +154,18 @@ pub fn fetch_cti_set(code: SetCode) -> SetResult<(), ()> {
         });
     }
 
+    let traits_description = traits_description(&cards, &mut sigils);
+
     Ok(Set {
         code,
         name: String::from("Custom TCG Inscryption"),
         cards,
-        sigils_description,
-    })
+        sigils,
+        traits_description,
+        index: None,
+        aliases: HashMap::new(),
+    }
+    .indexed())
 }
 
 /// Json scheme for Cti card.
@@ -3,11 +3,11 @@ use std::collections::HashMap;
 use serde::Deserialize;
 
 use crate::{
-    ext::desc::DescCosts, fetch::fetch_json, Attack, Card, Costs, Mox, Rarity, Set, SetCode,
-    Temple, Traits, TraitsFlag,
+    ext::desc::DescCosts, fetch::fetch_json, Attack, Card, Costs, Mox, Rarity, Set, SetCode, Sigil,
+    Temple, Traits, TraitsFlag, UNDEFINED_SIGIL,
 };
 
-use super::{SetError, SetResult};
+use super::{traits_description, SetError, SetResult};
 
 /// Fetch Descryption from the
 /// [sheet](https://docs.google.com/spreadsheets/d/1EjOtqUrjsMRl7wiVMN7tMuvAHvkw7snv1dNyFJIFbaE).
@@ -21,16 +21,14 @@ pub fn fetch_desc_set(code: SetCode) -> SetResult<(), DescCosts> {
         fetch_json(sigil_url).map_err(|e| SetError::FetchError(e, sigil_url.to_string()))?;
 
     let mut cards = Vec::with_capacity(card_raw.len());
-    let sigils_description = {
+    let mut sigils = {
         let mut h = HashMap::with_capacity(sigils.len());
         for s in sigils {
-            h.insert(s.name, s.text);
+            let sigil = Sigil::new(s.name.clone(), s.text, code);
+            h.insert(s.name, sigil);
         }
 
-        h.insert(
-            String::from("UNDEFINED SIGIL"),
-            "THIS SIGIL IS NOT DEFINED BY THE SET".to_owned(),
-        );
+        h.insert(UNDEFINED_SIGIL.to_owned(), Sigil::undefined(code));
 
         h
     };
@@ -136,10 +134,10 @@ pub fn fetch_desc_set(code: SetCode) -> SetResult<(), DescCosts> {
                     .split(", ")
                     .map(|s| {
                         let s = s.to_owned();
-                        if sigils_description.contains_key(&s) {
+                        if sigils.contains_key(&s) {
                             s
                         } else {
-                            String::from("UNDEFINED SIGIL")
+                            UNDEFINED_SIGIL.to_owned()
                         }
                     })
                     .collect()
@@ -169,12 +167,18 @@ pub fn fetch_desc_set(code: SetCode) -> SetResult<(), DescCosts> {
         cards.push(card);
     }
 
+    let traits_description = traits_description(&cards, &mut sigils);
+
     Ok(Set {
         code,
         name: String::from("Descryption"),
         cards,
-        sigils_description,
-    })
+        sigils,
+        traits_description,
+        index: None,
+        aliases: HashMap::new(),
+    }
+    .indexed())
 }
 
 fn is_empty(str: &str) -> bool {
@@ -7,8 +7,8 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    fetch::fetch_json, helper::FlagsExt, Attack, Card, Costs, Mox, Rarity, Set, SetCode, SpAtk,
-    Temple, Traits, TraitsFlag,
+    fetch::fetch_json, helper::FlagsExt, Attack, Card, Costs, Mox, Rarity, Set, SetCode, Sigil,
+    SpAtk, Temple, Traits, TraitsFlag, UNDEFINED_SIGIL,
 };
 
 use super::{SetError, SetResult};
@@ -19,16 +19,14 @@ pub fn fetch_imf_set(url: &str, code: SetCode) -> SetResult<(), ()> {
 
     let mut cards = Vec::with_capacity(set.cards.len() + 1);
 
-    let mut sigils_description = HashMap::with_capacity(set.sigils.len());
+    let mut sigils = HashMap::with_capacity(set.sigils.len());
 
-    for s in set.sigils {
-        sigils_description.insert(s.0, s.1);
+    for (name, text) in set.sigils {
+        let sigil = Sigil::new(name.clone(), text, code);
+        sigils.insert(name, sigil);
     }
 
-    sigils_description.insert(
-        String::from("UNDEFINED SIGIL"),
-        "THIS SIGIL IS NOT DEFINED BY THE SET".to_owned(),
-    );
+    sigils.insert(UNDEFINED_SIGIL.to_owned(), Sigil::undefined(code));
 
     for c in set.cards {
         let card = Card {
@@ -75,18 +73,18 @@ pub fn fetch_imf_set(url: &str, code: SetCode) -> SetResult<(), ()> {
                 .sigils
                 .into_iter()
                 .map(|s| {
-                    if sigils_description.contains_key(&s) {
+                    if sigils.contains_key(&s) {
                         s
                     } else {
-                        String::from("UNDEFINED SIGIL")
+                        UNDEFINED_SIGIL.to_owned()
                     }
                 })
                 .collect(),
 
-            costs: ((c.blood_cost > 0)
-                | (c.bone_cost > 0)
-                | (c.energy_cost > 0)
-                | (!c.mox_cost.is_empty()))
+            costs: (c.blood_cost > 0
+                || c.bone_cost > 0
+                || c.energy_cost > 0
+                || !c.mox_cost.is_empty())
             .then(|| Costs {
                 blood: c.blood_cost,
                 bone: c.bone_cost,
@@ -140,8 +138,12 @@ pub fn fetch_imf_set(url: &str, code: SetCode) -> SetResult<(), ()> {
         code,
         name: set.ruleset,
         cards,
-        sigils_description,
-    })
+        sigils,
+        traits_description: HashMap::new(),
+        index: None,
+        aliases: HashMap::new(),
+    }
+    .indexed())
 }
 
 /// Json scheme for IMF set.
@@ -20,9 +20,13 @@ pub mod ext;
 pub mod query;
 
 pub use data::cards::*;
+pub use data::index::*;
 pub use data::sets::*;
+pub use data::sigils::*;
 
 mod data {
     pub mod cards;
+    pub mod index;
     pub mod sets;
+    pub mod sigils;
 }
@@ -9,8 +9,12 @@
 //! ```
 
 pub use crate::{
-    ext::{aug::*, desc::*},
-    query::{FilterFn, Filters, QueryBuilder, QueryOrder, ToFilter},
+    ext::{aug::*, desc::*, ExtFields},
+    query::{
+        CostKind, Expr, Facets, FieldFilter, FilterFn, Filters, OwnedCard, OwnedQueryIter,
+        QueryBuilder, QueryError, QueryIter, QueryOrder, QueryParser, Shorthand, Stat, ToFilter,
+        Unique,
+    },
     *,
 };
 
@@ -3,6 +3,9 @@
 //! To query a card you first start with creating a [`QueryBuilder`] then build up your query using
 //! [`Filters`] then finally calling [`QueryBuilder::query`] to obtain a [`Query`].
 //!
+//! Filters can also be parse from the query syntax using [`parse`] or a [`QueryParser`] if you
+//! want to add your own keywords. The syntax is documented in [`QueryParser`].
+//!
 //! # Examples
 //!
 //! ```
@@ -28,12 +31,40 @@
 //! let result = query.query();
 //! ```
 
-use crate::{Attack, Card, Costs, Rarity, Set, SpAtk, Temple, Traits};
+use crate::ext::ExtFields;
+use crate::{
+    intersect, union, Attack, Card, Costs, Rarity, Set, SetCode, SetIndex, SpAtk, Temple, Traits,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 use std::vec;
 
+#[cfg(feature = "regex")]
+use regex::{Regex, RegexBuilder};
+
+mod error;
+mod expr;
+mod facets;
+mod iter;
+mod lexer;
+mod optimize;
+mod parser;
+mod unique;
+mod writer;
+
+pub use error::*;
+pub use expr::*;
+pub use facets::*;
+pub use iter::*;
+pub use lexer::*;
+pub use optimize::*;
+pub use parser::*;
+pub use unique::*;
+pub use writer::*;
+
 /// The result of a filters obtain by calling [`QueryBuilder::query`].
 #[derive(Debug)]
 pub struct Query<'a, E, C, F>
@@ -48,6 +79,21 @@ where
     pub filters: Vec<Filters<E, C, F>>,
 }
 
+impl<E, C, F> Query<'_, E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    /// Count the result cards grouped by temple, rarity, set, cost type, attack and health.
+    ///
+    /// Useful to show a breakdown of a broad query so the user can narrow it down.
+    #[must_use]
+    pub fn facets(&self) -> Facets {
+        Facets::new(self.cards.iter().copied())
+    }
+}
+
 impl<E, C, F> Display for Query<'_, E, C, F>
 where
     E: Clone,
@@ -68,7 +114,7 @@ where
 }
 
 /// Type alias for a filter function.
-pub type FilterFn<E, C> = Box<dyn Fn(&Card<E, C>) -> bool>;
+pub type FilterFn<E, C> = Box<dyn Fn(&Card<E, C>) -> bool + Send + Sync>;
 
 /// Query builder, it contain the set and is the main way to query cards.
 ///
@@ -109,12 +155,11 @@ where
     sets: Vec<&'a Set<E, C>>,
 
     filters: Vec<Filters<E, C, F>>,
-    funcs: Vec<FilterFn<E, C>>,
 }
 
 impl<'a, E, C, F> QueryBuilder<'a, E, C, F>
 where
-    C: Clone + PartialEq + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
     E: Clone + 'static,
     F: ToFilter<E, C> + 'static,
 {
@@ -156,7 +201,6 @@ where
         QueryBuilder {
             sets,
             filters: vec![],
-            funcs: vec![],
         }
     }
 
@@ -184,11 +228,7 @@ where
     /// ```
     #[must_use]
     pub fn with_filters(sets: Vec<&'a Set<E, C>>, filters: Vec<Filters<E, C, F>>) -> Self {
-        QueryBuilder {
-            funcs: filters.clone().into_iter().map(|f| f.to_fn()).collect(),
-            sets,
-            filters,
-        }
+        QueryBuilder { sets, filters }
     }
 
     /// Add a new filter to this query.
@@ -197,8 +237,7 @@ where
     /// instead
     #[must_use]
     pub fn add_filter(mut self, filter: Filters<E, C, F>) -> Self {
-        self.filters.push(filter.clone());
-        self.funcs.push(filter.to_fn());
+        self.filters.push(filter);
         self
     }
 
@@ -206,29 +245,65 @@ where
     ///
     /// If you want to use the builder pattern use [`add_filter`](QueryBuilder::add_filter) instead
     pub fn add_filter_mut(&mut self, filter: Filters<E, C, F>) {
-        self.filters.push(filter.clone());
-        self.funcs.push(filter.to_fn());
+        self.filters.push(filter);
     }
 
     /// Compile all the query and give you the result.
+    ///
+    /// The filters are run through [`optimize`] first so the order they are added in does not
+    /// matter. The [`Query`] still contain the filters as they were added.
+    ///
+    /// Set with a [`SetIndex`] use it to narrow down which card to check first.
     #[must_use]
     pub fn query(self) -> Query<'a, E, C, F> {
-        let filter = move |c: &Card<E, C>| self.funcs.iter().all(move |f| f(c));
-
         Query {
-            filters: self.filters,
-            cards: self
-                .sets
-                .iter()
-                .flat_map(|s| &s.cards)
-                .filter(|&c| filter(c))
-                .collect(),
+            filters: self.filters.clone(),
+            cards: self.query_iter().collect(),
+        }
+    }
+
+    /// Compile all the query and give you a lazy iterator over the result.
+    ///
+    /// Unlike [`query`](QueryBuilder::query) cards are only check when the iterator reach them so
+    /// you can stop early, like when you only need the first few results. If you need the results
+    /// to outlive the sets borrow use [`OwnedQueryIter`] instead.
+    #[must_use]
+    pub fn query_iter(self) -> QueryIter<'a, E, C, F> {
+        QueryIter::new(self.sets, self.filters)
+    }
+}
+
+/// Card properties that are too long or impossible to express with other filters.
+///
+/// These are use for [`Filters::Is`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shorthand {
+    /// Card with no cost, only the blood, bone, energy and mox component are check.
+    Free,
+    /// Card that are list in another card [`related`](Card::related) in the same set.
+    Token,
+    /// Card that cost more than 1 type of resource, like blood and bone.
+    Split,
+    /// Card that evolve into another card, these card have the fledgling sigil.
+    Evolves,
+    /// Card that belong to more than 1 temple.
+    MultiTemple,
+}
+
+impl Display for Shorthand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shorthand::Free => write!(f, "is free"),
+            Shorthand::Token => write!(f, "is a token"),
+            Shorthand::Split => write!(f, "have a split cost"),
+            Shorthand::Evolves => write!(f, "evolve"),
+            Shorthand::MultiTemple => write!(f, "belong to multiple temples"),
         }
     }
 }
 
 /// [`Ordering`](std::cmp::Ordering) extension for more ordering.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QueryOrder {
     /// Greater than another.
     Greater,
@@ -262,7 +337,10 @@ impl Display for QueryOrder {
 ///
 /// You can add custom filter by providing the `F` generic and implementing [`ToFilter`] trait for
 /// it.
-#[derive(Debug, Clone)]
+///
+/// Filters can be serialize with serde and convert back into the query syntax with
+/// [`ToFilter::to_query_string`]. Regex filters are serialize as their pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Filters<E, C, F>
 where
     E: Clone,
@@ -271,8 +349,27 @@ where
 {
     /// Filter for card name.
     ///
-    /// The value in this variant is the name to filter for.
+    /// The value in this variant is the name to filter for. Card the value is an alias of in
+    /// their set (see [`Set::aliases`]) also match.
     Name(String),
+    /// Filter for card name using regex.
+    ///
+    /// The value in this variant is the regex to match against the card name. Use
+    /// [`Filters::name_regex`] to compile one.
+    #[cfg(feature = "regex")]
+    #[serde(with = "regex_serde")]
+    NameRegex(Regex),
+    /// Filter for card description using regex.
+    ///
+    /// The value in this variant is the regex to match against the card description. Use
+    /// [`Filters::description_regex`] to compile one.
+    #[cfg(feature = "regex")]
+    #[serde(with = "regex_serde")]
+    DescriptionRegex(Regex),
+    /// Filter for the set the card belong to.
+    ///
+    /// The value in this variant is the set code to filter for.
+    Set(SetCode),
     /// Filter for card description.
     ///
     /// The value in this variant is the description to filter for.
@@ -290,6 +387,13 @@ where
     ///
     /// The value is the tribe or tribes to match against.
     Tribe(Option<String>),
+    /// Filter for card tribe using regex.
+    ///
+    /// The value in this variant is the regex to match against the card tribes. Card with no tribe
+    /// never match. Use [`Filters::tribe_regex`] to compile one.
+    #[cfg(feature = "regex")]
+    #[serde(with = "regex_serde")]
+    TribeRegex(Regex),
 
     /// Filter for the card attack.
     ///
@@ -302,10 +406,30 @@ where
     /// second is the value to compare against.
     Health(QueryOrder, isize),
 
+    /// Filter comparing 2 computed expressions.
+    ///
+    /// The first and last value are the expressions to compare, the middle value is what
+    /// qualifier or comparasion to compare them with. Card where either expression have no value
+    /// are not match, see [`Expr::eval`].
+    Expr(Expr, QueryOrder, Expr),
+
     /// Filter for card sigil
     ///
-    /// The value in this variant is the sigil name to filter for in the card sigils.
+    /// The value in this variant is the sigil name to filter for in the card sigils. A card match
+    /// if any of its sigil name contain the value, case-insensitively.
     Sigil(String),
+    /// Filter for card sigil description.
+    ///
+    /// The value in this variant is the text to search for in the card sigils description,
+    /// case-insensitively. The description is look up in [`Set::sigils`] so this
+    /// filter only match when compile with the sets being query like in [`QueryBuilder`] or
+    /// [`Filters::to_fn_with`].
+    SigilDescription(String),
+    /// Filter for the number of sigils on the card.
+    ///
+    /// The first value is what what qualifier or comparasion to compare the sigil count against, the
+    /// second is the value to compare against.
+    SigilCount(QueryOrder, usize),
 
     /// filter for card special attack.
     ///
@@ -323,9 +447,33 @@ where
     Costs(Option<Costs<C>>),
     /// Filter for card trait.
     ///
-    /// The value in this variant is trait table to filter for.
+    /// The value in this variant is trait table to filter for. A card match if it have every flags
+    /// and string traits in the table, string traits are compare case-insensitively. [`None`]
+    /// match card with no traits.
     Traits(Option<Traits>),
 
+    /// Filter for card related card.
+    ///
+    /// The value in this variant is part of a related card name to filter for. [`None`] match
+    /// card that have any related card.
+    Related(Option<String>),
+    /// Filter for card that are list as a related card of a card matching the inner filter.
+    ///
+    /// Only card in the same set are consider.
+    RelatedOf(Box<Filters<E, C, F>>),
+
+    /// Filter for a [`Shorthand`] card property.
+    Is(Shorthand),
+
+    /// Which card to keep when the same card appear in multiple sets.
+    ///
+    /// This is not really a filter, it match every card and [`QueryBuilder`] remove the
+    /// duplicates. It is only use at the top level and ignore when inside other logical filters.
+    Unique(Unique),
+
+    /// Logical `and` between 2 filters. Only needed when nesting inside other logical filters,
+    /// filters are already and together by default.
+    And(Box<Filters<E, C, F>>, Box<Filters<E, C, F>>),
     /// Logical `or` between 2 filters instead of the default and.
     Or(Box<Filters<E, C, F>>, Box<Filters<E, C, F>>),
     /// Logical `not` for a filter.
@@ -335,11 +483,69 @@ where
     Extra(F),
 
     #[doc(hidden)]
+    #[serde(skip)]
     McGuffin(Infallible, PhantomData<C>),
     #[doc(hidden)]
+    #[serde(skip)]
     Cake(Infallible, PhantomData<E>),
 }
 
+#[cfg(feature = "regex")]
+impl<E, C, F> Filters<E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    /// Compile a case-insensitive [`Filters::NameRegex`] from a pattern.
+    ///
+    /// # Examples
+    /// ```
+    /// use magpie_engine::prelude::*;
+    ///
+    /// let filter: Result<Filters<(), (), ()>, _> = Filters::name_regex(r"^(Mr|Ms)\.");
+    /// assert!(filter.is_ok());
+    ///
+    /// let filter: Result<Filters<(), (), ()>, _> = Filters::name_regex(r"(unclosed");
+    /// assert!(filter.is_err());
+    /// ```
+    pub fn name_regex(pattern: &str) -> Result<Self, regex::Error> {
+        compile_regex(pattern).map(Filters::NameRegex)
+    }
+
+    /// Compile a case-insensitive [`Filters::DescriptionRegex`] from a pattern.
+    pub fn description_regex(pattern: &str) -> Result<Self, regex::Error> {
+        compile_regex(pattern).map(Filters::DescriptionRegex)
+    }
+
+    /// Compile a case-insensitive [`Filters::TribeRegex`] from a pattern.
+    pub fn tribe_regex(pattern: &str) -> Result<Self, regex::Error> {
+        compile_regex(pattern).map(Filters::TribeRegex)
+    }
+}
+
+/// Compile a regex case-insensitively to be consistent with other text filters.
+#[cfg(feature = "regex")]
+fn compile_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+/// Serde helper to serialize regex as their pattern.
+#[cfg(feature = "regex")]
+mod regex_serde {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        super::compile_regex(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Traits for converting a type to a [`FilterFn`].
 ///
 /// The generic is for the cards extension.
@@ -350,6 +556,57 @@ where
 {
     /// Convert the value into a [`FilterFn`].
     fn to_fn(self) -> FilterFn<E, C>;
+
+    /// Convert the value back into the query syntax.
+    ///
+    /// Return [`None`] if the filter can't be express in the query syntax, which is the default.
+    fn to_query_string(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Filter for a field in the card extension.
+///
+/// The extension have to implement [`ExtFields`] so this is not a [`Filters`] variant, use it as
+/// your extra filter or wrap it in one instead. The field value is match case-insensitively and
+/// partially like [`Filters::Name`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldFilter {
+    /// The name of the field to filter.
+    pub field: String,
+    /// The value to filter for.
+    pub value: String,
+}
+
+impl FieldFilter {
+    /// Create a new field filter.
+    pub fn new(field: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl<E, C> ToFilter<E, C> for FieldFilter
+where
+    E: Clone + ExtFields,
+    C: Clone + PartialEq,
+{
+    fn to_fn(self) -> FilterFn<E, C> {
+        let value = self.value.to_lowercase();
+        Box::new(move |c| {
+            c.extra
+                .field(&self.field)
+                .is_some_and(|f| f.to_lowercase().contains(&value))
+        })
+    }
+}
+
+impl Display for FieldFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} includes {}", self.field, self.value)
+    }
 }
 
 /// Generate code to help with matching [`QueryOrder`].
@@ -366,29 +623,153 @@ macro_rules! match_query_order {
     };
 }
 
+/// Compile a list of filters using infomation from the sets being query.
+fn compile<E, C, F>(filters: Vec<Filters<E, C, F>>, sets: &[&Set<E, C>]) -> Vec<FilterFn<E, C>>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    filters.into_iter().map(|f| f.to_fn_with(sets)).collect()
+}
+
+/// Use the index to find the position of cards that could match all the filters.
+///
+/// Return [`None`] if the index can't narrow down any of the filters.
+fn candidates<E, C, F>(
+    index: &SetIndex,
+    code: SetCode,
+    filters: &[Filters<E, C, F>],
+) -> Option<Vec<usize>>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    filters
+        .iter()
+        .filter_map(|f| lookup(index, code, f))
+        .reduce(|a, b| intersect(&a, &b))
+}
+
+/// Use the index to find the position of cards that could match a filter. The result may
+/// contain card that don't match but never miss card that do.
+fn lookup<E, C, F>(index: &SetIndex, code: SetCode, filter: &Filters<E, C, F>) -> Option<Vec<usize>>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    match filter {
+        Filters::Set(c) if !c.eq_ignore_ascii_case(&code) => Some(vec![]),
+        Filters::Sigil(s) => Some(index.sigil(&s.to_lowercase())),
+        Filters::Tribe(Some(t)) => index.tribe(&t.to_lowercase()),
+        Filters::Tribe(None) => Some(index.tribeless().to_vec()),
+        Filters::Rarity(r) => Some(index.rarity(r).to_vec()),
+        Filters::Temple(t) => Some(index.temple(*t)),
+        Filters::Costs(None) => Some(index.costless().to_vec()),
+        Filters::Costs(Some(c)) => Some(
+            [
+                index.blood(c.blood),
+                index.bone(c.bone),
+                index.energy(c.energy),
+            ]
+            .into_iter()
+            .fold(index.mox(c.mox), |a, b| intersect(&a, b)),
+        ),
+        Filters::And(a, b) => match (lookup(index, code, a), lookup(index, code, b)) {
+            (Some(a), Some(b)) => Some(intersect(&a, &b)),
+            (a, b) => a.or(b),
+        },
+        Filters::Or(a, b) => Some(union(&lookup(index, code, a)?, &lookup(index, code, b)?)),
+        _ => None,
+    }
+}
+
+/// Check if a string contain an already lowercased needle ignoring case.
+///
+/// Most card text are ascii so we can compare without allocating a lowercase copy of the text
+/// for every card.
+fn contains_lowercase(haystack: &str, needle: &str) -> bool {
+    if haystack.is_ascii() && needle.is_ascii() {
+        needle.is_empty()
+            || haystack
+                .as_bytes()
+                .windows(needle.len())
+                .any(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+    } else {
+        haystack.to_lowercase().contains(needle)
+    }
+}
+
 impl<E, C, F> ToFilter<E, C> for Filters<E, C, F>
 where
     E: Clone + 'static,
-    C: Clone + PartialEq + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
     F: ToFilter<E, C> + 'static,
 {
     fn to_fn(self) -> FilterFn<E, C> {
+        self.to_fn_with(&[])
+    }
+
+    fn to_query_string(&self) -> Option<String> {
+        writer::filter_to_query(self)
+    }
+}
+
+impl<E, C, F> Filters<E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    /// Convert this filter into a [`FilterFn`] using infomation from the sets being query.
+    ///
+    /// Some filter like [`Filters::SigilDescription`] need infomation that are store in the set
+    /// instead of the card. Calling [`to_fn`](ToFilter::to_fn) is the same as calling this with no
+    /// set.
+    pub fn to_fn_with(self, sets: &[&Set<E, C>]) -> FilterFn<E, C> {
         match self {
             Filters::Name(name) => {
-                Box::new(move |c| c.name.to_lowercase().contains(&name.to_lowercase()))
+                // the cards the name is an alias of in each set
+                let aliased: Vec<(SetCode, String)> = sets
+                    .iter()
+                    .filter_map(|s| s.alias(&name).map(|n| (s.code, n.to_lowercase())))
+                    .collect();
+
+                let name = name.to_lowercase();
+                Box::new(move |c| {
+                    contains_lowercase(&c.name, &name)
+                        || aliased
+                            .iter()
+                            .any(|(code, n)| c.set == *code && c.name.to_lowercase() == *n)
+                })
             }
             Filters::Description(desc) => {
-                Box::new(move |c| c.description.to_lowercase().contains(&desc.to_lowercase()))
+                let desc = desc.to_lowercase();
+                Box::new(move |c| contains_lowercase(&c.description, &desc))
             }
+            #[cfg(feature = "regex")]
+            Filters::NameRegex(r) => Box::new(move |c| r.is_match(&c.name)),
+            #[cfg(feature = "regex")]
+            Filters::DescriptionRegex(r) => Box::new(move |c| r.is_match(&c.description)),
+            Filters::Set(code) => Box::new(move |c| c.set.eq_ignore_ascii_case(&code)),
 
             Filters::Rarity(rarity) => Box::new(move |c| c.rarity == rarity),
             Filters::Temple(temple) => Box::new(move |c| c.temple == temple),
-            Filters::Tribe(tribes) => Box::new(move |c| match &c.tribes {
-                Some(tr) if tribes.is_some() => tr
-                    .to_lowercase()
-                    .contains(&tribes.as_ref().unwrap().to_lowercase()),
-                _ => c.tribes == tribes,
-            }),
+            Filters::Tribe(Some(tribe)) => {
+                let tribe = tribe.to_lowercase();
+                Box::new(move |c| {
+                    c.tribes
+                        .as_ref()
+                        .is_some_and(|t| contains_lowercase(t, &tribe))
+                })
+            }
+            Filters::Tribe(None) => Box::new(|c| c.tribes.is_none()),
+            #[cfg(feature = "regex")]
+            Filters::TribeRegex(r) => {
+                Box::new(move |c| c.tribes.as_ref().is_some_and(|t| r.is_match(t)))
+            }
             Filters::Attack(ord, attack) => Box::new(move |c| {
                 if let Attack::Num(a) = c.attack {
                     match_query_order!(ord, a, attack)
@@ -399,15 +780,41 @@ where
             Filters::Health(ord, health) => {
                 Box::new(move |c| match_query_order!(ord, c.health, health))
             }
+            Filters::Expr(a, ord, b) => Box::new(move |c| match (a.eval(c), b.eval(c)) {
+                (Some(a), Some(b)) => match_query_order!(ord, a, b),
+                _ => false,
+            }),
             Filters::Sigil(s) => {
                 let lower = s.to_lowercase();
+                Box::new(move |c| c.sigils.iter().any(|s| contains_lowercase(s, &lower)))
+            }
+            Filters::SigilDescription(text) => {
+                let lower = text.to_lowercase();
+
+                // look up which sigils match ahead of time so we don't have to do it per card
+                let matches: HashMap<SetCode, HashSet<String>> = sets
+                    .iter()
+                    .map(|s| {
+                        (
+                            s.code,
+                            s.sigils
+                                .iter()
+                                .filter(|(_, d)| d.description.to_lowercase().contains(&lower))
+                                .map(|(n, _)| n.clone())
+                                .collect(),
+                        )
+                    })
+                    .collect();
+
                 Box::new(move |c| {
-                    c.sigils
-                        .iter()
-                        .map(|s| s.to_lowercase())
-                        .any(|s| s.eq(&lower))
+                    matches
+                        .get(&c.set)
+                        .is_some_and(|m| c.sigils.iter().any(|s| m.contains(s)))
                 })
             }
+            Filters::SigilCount(ord, count) => {
+                Box::new(move |c| match_query_order!(ord, c.sigils.len(), count))
+            }
             Filters::SpAtk(a) => Box::new(move |c| {
                 if let Attack::SpAtk(sp) = &c.attack {
                     *sp == a
@@ -423,16 +830,110 @@ where
                 }
             }),
             Filters::Costs(cost) => Box::new(move |c| c.costs == cost),
-            Filters::Traits(traits) => Box::new(move |c| c.traits == traits),
+            Filters::Traits(traits) => Box::new(move |c| match (&c.traits, &traits) {
+                (Some(tr), Some(traits)) => tr.contains(traits),
+                (tr, None) => tr.as_ref().is_none_or(Traits::is_empty),
+                (None, Some(_)) => false,
+            }),
+
+            Filters::Related(None) => Box::new(|c| !c.related.is_empty()),
+            Filters::Related(Some(name)) => {
+                let name = name.to_lowercase();
+                Box::new(move |c| c.related.iter().any(|r| contains_lowercase(r, &name)))
+            }
+            Filters::RelatedOf(f) => {
+                let f = f.to_fn_with(sets);
+
+                // collect the related card of every matching card ahead of time
+                let related: HashMap<SetCode, HashSet<String>> = sets
+                    .iter()
+                    .map(|s| {
+                        (
+                            s.code,
+                            s.cards
+                                .iter()
+                                .filter(|&c| f(c))
+                                .flat_map(|c| &c.related)
+                                .map(|r| r.to_lowercase())
+                                .collect(),
+                        )
+                    })
+                    .collect();
+
+                Box::new(move |c| {
+                    related
+                        .get(&c.set)
+                        .is_some_and(|r| r.contains(&c.name.to_lowercase()))
+                })
+            }
+
+            Filters::Is(Shorthand::Free) => Box::new(|c| {
+                c.costs.as_ref().is_none_or(|c| {
+                    c.blood == 0 && c.bone == 0 && c.energy == 0 && c.mox.is_empty()
+                })
+            }),
+            Filters::Is(Shorthand::Token) => {
+                // collect every related card name ahead of time
+                let tokens: HashMap<SetCode, HashSet<String>> = sets
+                    .iter()
+                    .map(|s| {
+                        (
+                            s.code,
+                            s.cards
+                                .iter()
+                                .flat_map(|c| &c.related)
+                                .map(|r| r.to_lowercase())
+                                .collect(),
+                        )
+                    })
+                    .collect();
+
+                Box::new(move |c| {
+                    tokens
+                        .get(&c.set)
+                        .is_some_and(|t| t.contains(&c.name.to_lowercase()))
+                })
+            }
+            Filters::Is(Shorthand::Split) => Box::new(|c| {
+                c.costs.as_ref().is_some_and(|c| {
+                    [c.blood != 0, c.bone != 0, c.energy != 0, !c.mox.is_empty()]
+                        .into_iter()
+                        .filter(|&b| b)
+                        .count()
+                        > 1
+                })
+            }),
+            Filters::Is(Shorthand::Evolves) => Box::new(|c| {
+                c.sigils
+                    .iter()
+                    .any(|s| s.to_lowercase().starts_with("fledgling"))
+            }),
+            Filters::Is(Shorthand::MultiTemple) => Box::new(|c| c.temple.bits().count_ones() > 1),
+
+            Filters::Unique(_) => Box::new(|_| true),
+
+            Filters::And(a, b) => {
+                let mut filters = vec![];
+                optimize::flatten(Filters::And(a, b), &mut filters);
+                optimize::sort_by_cost(&mut filters);
+
+                let funcs = compile(filters, sets);
+                Box::new(move |c| funcs.iter().all(|f| f(c)))
+            }
 
             Filters::Or(a, b) => {
-                let a = a.to_fn();
-                let b = b.to_fn();
-                Box::new(move |c| a(c) || b(c))
+                let mut filters = vec![];
+                optimize::flatten_or(Filters::Or(a, b), &mut filters);
+                optimize::sort_by_cost(&mut filters);
+
+                let funcs = compile(filters, sets);
+                Box::new(move |c| funcs.iter().any(|f| f(c)))
             }
 
+            // unique is not a real filter so negating it does nothing
+            Filters::Not(f) if matches!(*f, Filters::Unique(_)) => Box::new(|_| true),
             Filters::Not(f) => {
-                let f = f.to_fn();
+                let f = f.to_fn_with(sets);
                 Box::new(move |c| !f(c))
             }
 
@@ -463,15 +964,26 @@ where
         match self {
             Filters::Name(n) => write!(f, "name includes {n}"),
             Filters::Description(d) => write!(f, "description includes {d}"),
+            #[cfg(feature = "regex")]
+            Filters::NameRegex(r) => write!(f, "name match /{r}/"),
+            #[cfg(feature = "regex")]
+            Filters::DescriptionRegex(r) => write!(f, "description match /{r}/"),
+            Filters::Set(s) => write!(f, "is from the {s} set"),
             Filters::Rarity(r) => write!(f, "is {r}"),
             Filters::Temple(t) => write!(f, "from the {t} temple"),
             Filters::Tribe(t) => match t {
                 None => write!(f, "is tribeless"),
                 Some(t) => write!(f, "is a {t}"),
             },
+            #[cfg(feature = "regex")]
+            Filters::TribeRegex(r) => write!(f, "tribe match /{r}/"),
             Filters::Attack(o, a) => write!(f, "attack {o} {a}"),
             Filters::Health(o, a) => write!(f, "health {o} {a}"),
+            Filters::Expr(a, o, b) => write!(f, "{a} {o} {b}"),
             Filters::Sigil(s) => write!(f, "have {s}"),
+            Filters::SigilDescription(s) => write!(f, "have a sigil mentioning {s}"),
+            Filters::SigilCount(QueryOrder::Equal, 0) => write!(f, "is vanilla"),
+            Filters::SigilCount(o, c) => write!(f, "have {o} {c} sigils"),
             Filters::SpAtk(a) => write!(f, "attack value is {a}"),
             Filters::StrAtk(s) => write!(f, "attack value is {s}"),
             Filters::Costs(c) => match c {
@@ -482,8 +994,19 @@ where
                 None => write!(f, "is traitless"),
                 Some(t) => write!(f, "is {t}"),
             },
+            Filters::Related(None) => write!(f, "have related cards"),
+            Filters::Related(Some(r)) => write!(f, "have a related card matching {r}"),
+            Filters::RelatedOf(a) => write!(f, "is related to cards that {a}"),
+            Filters::Is(s) => write!(f, "{s}"),
+            Filters::Unique(Unique::Names) => write!(f, "are unique by name"),
+            Filters::Unique(Unique::Prints) => write!(f, "are unique by printing"),
+            Filters::Unique(Unique::All) => write!(f, "include every printing"),
+            Filters::And(a, b) => write!(f, "({a} and {b})"),
             Filters::Or(a, b) => write!(f, "({a} or {b})"),
-            Filters::Not(a) => write!(f, "not ({a})"),
+            Filters::Not(a) => match **a {
+                Filters::And(..) | Filters::Or(..) => write!(f, "not {a}"),
+                _ => write!(f, "not ({a})"),
+            },
             Filters::Extra(e) => write!(f, "{e}"),
             Filters::McGuffin(..) | Filters::Cake(..) => unreachable!(),
         }
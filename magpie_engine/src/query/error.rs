@@ -0,0 +1,145 @@
+//! Error type for the query lexer and parser.
+
+use std::fmt::Display;
+
+use super::{Span, Token};
+
+/// The kind of [`QueryError`].
+#[derive(Debug)]
+pub enum QueryErrorKind {
+    /// Error when encountering a character the lexer does not know.
+    UnexpectedChar(char),
+    /// Error when a string is missing the closing `"`.
+    UnterminatedString,
+    /// Error when a regex is missing the closing `/`.
+    UnterminatedRegex,
+    /// Error when encountering an unknown keyword.
+    InvalidKeyword(Token),
+    /// Error when missing something the parser expected.
+    ///
+    /// Contains a description of what was expected and what received.
+    Expected(&'static str, Token),
+    /// Error when a keyword is given a value it does not understand.
+    ///
+    /// Contains the keyword and the value.
+    InvalidValue(&'static str, String),
+    /// Error when a regex keyword is given an invalid regex.
+    #[cfg(feature = "regex")]
+    Regex(regex::Error),
+    /// Error return by an extra keyword registered with
+    /// [`QueryParser::keyword`](super::QueryParser::keyword).
+    Extra(String),
+    /// Error when groups or negative numbers are nested past
+    /// [`MAX_DEPTH`](super::MAX_DEPTH).
+    TooDeep,
+}
+
+impl Display for QueryErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryErrorKind::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            QueryErrorKind::UnterminatedString => write!(f, "missing closing `\"` for string"),
+            QueryErrorKind::UnterminatedRegex => write!(f, "missing closing `/` for regex"),
+            QueryErrorKind::InvalidKeyword(tk) => write!(f, "unknown keyword {tk}"),
+            QueryErrorKind::Expected(what, Token::Eof) => write!(f, "expected {what}"),
+            QueryErrorKind::Expected(what, tk) => write!(f, "expected {what} but found {tk}"),
+            QueryErrorKind::InvalidValue(kw, value) => write!(f, "invalid {kw} `{value}`"),
+            #[cfg(feature = "regex")]
+            QueryErrorKind::Regex(err) => {
+                // regex error are multi line with their own underline, only keep the reason
+                let err = err.to_string();
+                let reason = err.lines().last().unwrap_or_default();
+                write!(f, "invalid regex, {}", reason.trim_start_matches("error: "))
+            }
+            QueryErrorKind::Extra(err) => write!(f, "{err}"),
+            QueryErrorKind::TooDeep => write!(f, "query nested too deeply"),
+        }
+    }
+}
+
+/// Error when tokenizing or parsing a query.
+///
+/// The error know where in the query it happen so you can use [`QueryError::underline`] to show
+/// the user where the problem is.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+///
+/// let query = "h<3 a>=";
+/// let err = query::parse::<(), (), ()>(query).unwrap_err();
+///
+/// assert_eq!(err.to_string(), "expected a number after `a>=` at column 8");
+/// assert_eq!(err.underline(query), "h<3 a>=\n       ^");
+/// ```
+#[derive(Debug)]
+pub struct QueryError {
+    /// What went wrong.
+    pub kind: QueryErrorKind,
+    /// Where in the query it went wrong.
+    pub span: Span,
+    column: usize,
+    after: Option<String>,
+}
+
+impl QueryError {
+    /// Create a new error at a span of the given query.
+    #[must_use]
+    pub fn new(kind: QueryErrorKind, span: Span, query: &str) -> Self {
+        Self {
+            kind,
+            span,
+            column: query.get(..span.start).map_or(0, |s| s.chars().count()) + 1,
+            after: None,
+        }
+    }
+
+    /// Add the text that come before the error for context.
+    #[must_use]
+    pub fn after(mut self, text: &str) -> Self {
+        let text = text.trim();
+        if !text.is_empty() {
+            self.after = Some(text.to_owned());
+        }
+        self
+    }
+
+    /// The column the error start at, counting from 1.
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Return the query with a caret underline below where the error is.
+    #[must_use]
+    pub fn underline(&self, query: &str) -> String {
+        let width = query
+            .get(self.span.start..self.span.end)
+            .map_or(0, |s| s.chars().count())
+            .max(1);
+
+        format!(
+            "{query}\n{}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.kind, &self.after) {
+            (QueryErrorKind::Expected(what, tk), Some(after)) => {
+                write!(f, "expected {what} after `{after}`")?;
+                if tk != &Token::Eof {
+                    write!(f, " but found {tk}")?;
+                }
+            }
+            (kind, _) => write!(f, "{kind}")?,
+        }
+
+        write!(f, " at column {}", self.column)
+    }
+}
+
+impl std::error::Error for QueryError {}
@@ -0,0 +1,165 @@
+//! Implementation for stat expression use in [`Filters::Expr`](super::Filters::Expr).
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Attack, Card};
+
+/// Card value that can be use in an [`Expr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stat {
+    /// The card numeric attack. Card with special attack have no value.
+    Attack,
+    /// The card health.
+    Health,
+    /// The card blood cost.
+    Blood,
+    /// The card bone cost.
+    Bone,
+    /// The card energy cost.
+    Energy,
+    /// The card total mox count.
+    Mox,
+    /// The number of sigil on the card.
+    SigilCount,
+}
+
+impl Stat {
+    /// Get the value of this stat from a card.
+    ///
+    /// Return [`None`] if the card don't have a numeric value for this stat like a card with
+    /// special attack.
+    #[allow(clippy::cast_possible_wrap)] // no card will have that many sigils or moxes
+    pub fn value<E, C>(self, card: &Card<E, C>) -> Option<isize>
+    where
+        E: Clone,
+        C: Clone + PartialEq,
+    {
+        Some(match self {
+            Stat::Attack => match card.attack {
+                Attack::Num(a) => a,
+                _ => return None,
+            },
+            Stat::Health => card.health,
+            Stat::Blood => card.costs.as_ref().map_or(0, |c| c.blood),
+            Stat::Bone => card.costs.as_ref().map_or(0, |c| c.bone),
+            Stat::Energy => card.costs.as_ref().map_or(0, |c| c.energy),
+            Stat::Mox => card.costs.as_ref().map_or(0, |c| {
+                if let Some(m) = &c.mox_count {
+                    (m.o + m.g + m.b + m.y + m.r + m.e + m.p + m.k) as isize
+                } else {
+                    c.mox.iter().count() as isize
+                }
+            }),
+            Stat::SigilCount => card.sigils.len() as isize,
+        })
+    }
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Stat::Attack => "attack",
+                Stat::Health => "health",
+                Stat::Blood => "blood cost",
+                Stat::Bone => "bone cost",
+                Stat::Energy => "energy cost",
+                Stat::Mox => "mox cost",
+                Stat::SigilCount => "sigil count",
+            }
+        )
+    }
+}
+
+/// Arithmetic expression that can be evaluated against a card.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+///
+/// // attack + health
+/// let expr = Expr::Add(
+///     Box::new(Expr::Stat(Stat::Attack)),
+///     Box::new(Expr::Stat(Stat::Health)),
+/// );
+///
+/// assert_eq!(expr.to_string(), "attack + health");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expr {
+    /// A constant number.
+    Num(isize),
+    /// A value from the card.
+    Stat(Stat),
+    /// Add 2 expressions.
+    Add(Box<Expr>, Box<Expr>),
+    /// Subtract the second expression from the first.
+    Sub(Box<Expr>, Box<Expr>),
+    /// Multiply 2 expressions.
+    Mul(Box<Expr>, Box<Expr>),
+    /// Divide the first expression by the second.
+    Div(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate this expression against a card.
+    ///
+    /// Return [`None`] if any [`Stat`] have no value for the card, when dividing by zero or on
+    /// overflow.
+    pub fn eval<E, C>(&self, card: &Card<E, C>) -> Option<isize>
+    where
+        E: Clone,
+        C: Clone + PartialEq,
+    {
+        match self {
+            Expr::Num(n) => Some(*n),
+            Expr::Stat(s) => s.value(card),
+            Expr::Add(a, b) => a.eval(card)?.checked_add(b.eval(card)?),
+            Expr::Sub(a, b) => a.eval(card)?.checked_sub(b.eval(card)?),
+            Expr::Mul(a, b) => a.eval(card)?.checked_mul(b.eval(card)?),
+            Expr::Div(a, b) => a.eval(card)?.checked_div(b.eval(card)?),
+        }
+    }
+
+    /// Binding power of this expression use to decide when to add parenthesis.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Num(_) | Expr::Stat(_) => 2,
+            Expr::Mul(..) | Expr::Div(..) => 1,
+            Expr::Add(..) | Expr::Sub(..) => 0,
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (a, op, b) = match self {
+            Expr::Num(n) => return write!(f, "{n}"),
+            Expr::Stat(s) => return write!(f, "{s}"),
+            Expr::Add(a, b) => (a, "+", b),
+            Expr::Sub(a, b) => (a, "-", b),
+            Expr::Mul(a, b) => (a, "*", b),
+            Expr::Div(a, b) => (a, "/", b),
+        };
+
+        // the right side need parenthesis on the same precedence because `-` and `/` are not
+        // associative
+        if a.precedence() < self.precedence() {
+            write!(f, "({a})")?;
+        } else {
+            write!(f, "{a}")?;
+        }
+
+        write!(f, " {op} ")?;
+
+        if b.precedence() <= self.precedence() {
+            write!(f, "({b})")
+        } else {
+            write!(f, "{b}")
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Implementation of faceted counts for query results.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::{Attack, Card, Rarity, SetCode, Temple};
+
+/// The kind of cost a card have, use for [`Facets::cost_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CostKind {
+    /// Card with blood cost.
+    Blood,
+    /// Card with bone cost.
+    Bone,
+    /// Card with energy cost.
+    Energy,
+    /// Card with mox cost.
+    Mox,
+    /// Card with no cost.
+    Free,
+}
+
+impl Display for CostKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CostKind::Blood => "blood",
+                CostKind::Bone => "bone",
+                CostKind::Energy => "energy",
+                CostKind::Mox => "mox",
+                CostKind::Free => "free",
+            }
+        )
+    }
+}
+
+/// Counts of the query results grouped by a few card properties, obtain by calling
+/// [`Query::facets`](super::Query::facets).
+///
+/// The temple, rarity, set and cost type counts are sorted from the most common to the least and
+/// only contain value with at least 1 card. Card that belong to multiple temple or have multiple
+/// cost type are count once for each of them.
+#[derive(Debug, Clone, Default)]
+pub struct Facets {
+    /// Number of card in each temple.
+    pub temple: Vec<(Temple, usize)>,
+    /// Number of card of each rarity.
+    pub rarity: Vec<(Rarity, usize)>,
+    /// Number of card from each set.
+    pub set: Vec<(SetCode, usize)>,
+    /// Number of card with each cost type.
+    pub cost_type: Vec<(CostKind, usize)>,
+    /// Number of card with each attack value. Card with special attack are not count.
+    pub attack: BTreeMap<isize, usize>,
+    /// Number of card with each health value.
+    pub health: BTreeMap<isize, usize>,
+}
+
+impl Facets {
+    /// Count the facets of a list of cards.
+    #[must_use]
+    pub fn new<'a, E, C>(cards: impl IntoIterator<Item = &'a Card<E, C>>) -> Self
+    where
+        E: Clone + 'a,
+        C: Clone + PartialEq + 'a,
+    {
+        let mut temple = vec![];
+        let mut rarity = vec![];
+        let mut set = vec![];
+        let mut cost_type = vec![];
+        let mut attack = BTreeMap::new();
+        let mut health = BTreeMap::new();
+
+        for card in cards {
+            for t in card.temple.iter() {
+                count(&mut temple, t);
+            }
+            count(&mut rarity, card.rarity.clone());
+            count(&mut set, card.set);
+
+            match &card.costs {
+                Some(c) if c.blood != 0 || c.bone != 0 || c.energy != 0 || !c.mox.is_empty() => {
+                    for (has, kind) in [
+                        (c.blood != 0, CostKind::Blood),
+                        (c.bone != 0, CostKind::Bone),
+                        (c.energy != 0, CostKind::Energy),
+                        (!c.mox.is_empty(), CostKind::Mox),
+                    ] {
+                        if has {
+                            count(&mut cost_type, kind);
+                        }
+                    }
+                }
+                _ => count(&mut cost_type, CostKind::Free),
+            }
+
+            if let Attack::Num(a) = card.attack {
+                *attack.entry(a).or_default() += 1;
+            }
+            *health.entry(card.health).or_default() += 1;
+        }
+
+        Facets {
+            temple: sorted(temple),
+            rarity: sorted(rarity),
+            set: sorted(set),
+            cost_type: sorted(cost_type),
+            attack,
+            health,
+        }
+    }
+}
+
+/// Count a value, we use a vec instead of a map since there are only a few value and some of
+/// them can't be hash.
+fn count<T: PartialEq>(counts: &mut Vec<(T, usize)>, value: T) {
+    match counts.iter_mut().find(|(v, _)| *v == value) {
+        Some((_, n)) => *n += 1,
+        None => counts.push((value, 1)),
+    }
+}
+
+/// Sort the counts from most to least common, keeping the order they were first seen in for
+/// ties.
+fn sorted<T>(mut counts: Vec<(T, usize)>) -> Vec<(T, usize)> {
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    counts
+}
@@ -0,0 +1,296 @@
+//! Lazy iterators over query results.
+//!
+//! [`QueryIter`] borrow the sets like [`QueryBuilder`](super::QueryBuilder) while
+//! [`OwnedQueryIter`] hold the sets in an [`Arc`] so the results can outlive any lock the sets
+//! are behind.
+
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+use std::vec;
+
+use crate::{Card, Set};
+
+use super::{candidates, compile, optimize, FilterFn, Filters, Seen, ToFilter, Unique};
+
+/// The compiled filters and the position of the card being check in the current set.
+struct Matcher<E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    plan: Vec<Filters<E, C, F>>,
+    funcs: Vec<FilterFn<E, C>>,
+    positions: Positions,
+    /// The filters contradict each other so nothing can match.
+    never: bool,
+    unique: Unique,
+}
+
+/// Position of the card that could match in the current set.
+enum Positions {
+    All(Range<usize>),
+    Index(vec::IntoIter<usize>),
+}
+
+impl<E, C, F> Matcher<E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    fn new(filters: Vec<Filters<E, C, F>>, sets: &[&Set<E, C>]) -> Self {
+        let (plan, never) = match optimize(filters) {
+            Some(plan) => (plan, false),
+            None => (vec![], true),
+        };
+
+        // only the last top level unique mode is use
+        let unique = plan
+            .iter()
+            .rev()
+            .find_map(|f| match f {
+                Filters::Unique(u) => Some(*u),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Matcher {
+            funcs: compile(plan.clone(), sets),
+            plan,
+            positions: Positions::All(0..0),
+            never,
+            unique,
+        }
+    }
+
+    /// Start matching card in a new set.
+    fn start(&mut self, set: &Set<E, C>) {
+        if self.never {
+            return;
+        }
+
+        // ignore outdated index
+        let index = set.index.as_ref().filter(|i| i.len() == set.cards.len());
+
+        self.positions = match index.and_then(|i| candidates(i, set.code, &self.plan)) {
+            Some(pos) => Positions::Index(pos.into_iter()),
+            None => Positions::All(0..set.cards.len()),
+        };
+    }
+
+    /// Get the position of the next matching card in the current set.
+    fn next(&mut self, set: &Set<E, C>) -> Option<usize> {
+        let funcs = &self.funcs;
+        let matches = |&i: &usize| funcs.iter().all(|f| f(&set.cards[i]));
+
+        match &mut self.positions {
+            Positions::All(range) => range.find(matches),
+            Positions::Index(pos) => pos.find(matches),
+        }
+    }
+}
+
+/// Lazy iterator over the cards matching a query, obtain by calling
+/// [`QueryBuilder::query_iter`](super::QueryBuilder::query_iter).
+///
+/// Cards are only check when you ask for them so taking the first few results is cheaper than
+/// calling [`QueryBuilder::query`](super::QueryBuilder::query).
+pub struct QueryIter<'a, E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    matcher: Matcher<E, C, F>,
+    sets: vec::IntoIter<&'a Set<E, C>>,
+    current: Option<&'a Set<E, C>>,
+    seen: Seen<&'a Card<E, C>>,
+}
+
+impl<'a, E, C, F> QueryIter<'a, E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    pub(super) fn new(sets: Vec<&'a Set<E, C>>, filters: Vec<Filters<E, C, F>>) -> Self {
+        let matcher = Matcher::new(filters, &sets);
+        QueryIter {
+            seen: Seen::new(matcher.unique),
+            matcher,
+            sets: sets.into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'a, E, C, F> Iterator for QueryIter<'a, E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    type Item = &'a Card<E, C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(set) = self.current {
+                while let Some(i) = self.matcher.next(set) {
+                    if self.seen.insert(&set.cards[i]) {
+                        return Some(&set.cards[i]);
+                    }
+                }
+            }
+
+            let set = self.sets.next()?;
+            self.matcher.start(set);
+            self.current = Some(set);
+        }
+    }
+}
+
+/// A card from a set shared with an [`Arc`].
+///
+/// Deref to the [`Card`] so you can use it like a normal card reference.
+#[derive(Debug)]
+pub struct OwnedCard<E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    set: Arc<Set<E, C>>,
+    pos: usize,
+}
+
+impl<E, C> OwnedCard<E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    /// The set this card belong to.
+    #[must_use]
+    pub fn set(&self) -> &Arc<Set<E, C>> {
+        &self.set
+    }
+}
+
+impl<E, C> Clone for OwnedCard<E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    fn clone(&self) -> Self {
+        OwnedCard {
+            set: Arc::clone(&self.set),
+            pos: self.pos,
+        }
+    }
+}
+
+impl<E, C> Deref for OwnedCard<E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    type Target = Card<E, C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.set.cards[self.pos]
+    }
+}
+
+/// Lazy iterator over the cards matching a query that own its sets.
+///
+/// This work like [`QueryIter`] but the sets are share with an [`Arc`] instead of borrowed, so
+/// the iterator can be kept around after releasing any lock the sets are behind. The iterator is
+/// [`Send`] so it can also be hold across an `.await`.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use magpie_engine::prelude::*;
+///
+/// let set: Set<(), ()> = Set {
+///     code: SetCode::new("abc").unwrap(),
+///     name: String::from("Example"),
+///     cards: vec![],
+///     sigils: Default::default(),
+///     traits_description: Default::default(),
+///     index: None,
+///     aliases: Default::default(),
+/// };
+///
+/// let mut results: OwnedQueryIter<(), (), ()> =
+///     OwnedQueryIter::new(vec![Arc::new(set)], vec![Filters::Health(QueryOrder::Greater, 3)]);
+///
+/// // take the first page of result
+/// let page: Vec<_> = results.by_ref().take(10).collect();
+/// assert!(page.is_empty());
+///
+/// // the iterator can be send to another thread or hold across an await
+/// fn is_send<T: Send>(_: &T) {}
+/// is_send(&results);
+/// ```
+pub struct OwnedQueryIter<E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    matcher: Matcher<E, C, F>,
+    sets: vec::IntoIter<Arc<Set<E, C>>>,
+    current: Option<Arc<Set<E, C>>>,
+    seen: Seen<OwnedCard<E, C>>,
+}
+
+impl<E, C, F> OwnedQueryIter<E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    /// Compile the filters and create a lazy iterator over the matching cards of the sets.
+    #[must_use]
+    pub fn new(sets: Vec<Arc<Set<E, C>>>, filters: Vec<Filters<E, C, F>>) -> Self {
+        let refs: Vec<&Set<E, C>> = sets.iter().map(Deref::deref).collect();
+
+        let matcher = Matcher::new(filters, &refs);
+        OwnedQueryIter {
+            seen: Seen::new(matcher.unique),
+            matcher,
+            sets: sets.into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<E, C, F> Iterator for OwnedQueryIter<E, C, F>
+where
+    E: Clone + 'static,
+    C: Clone + PartialEq + Send + Sync + 'static,
+    F: ToFilter<E, C> + 'static,
+{
+    type Item = OwnedCard<E, C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(set) = &self.current {
+                while let Some(pos) = self.matcher.next(set) {
+                    let card = OwnedCard {
+                        set: Arc::clone(set),
+                        pos,
+                    };
+
+                    if self.seen.insert(card.clone()) {
+                        return Some(card);
+                    }
+                }
+            }
+
+            let set = self.sets.next()?;
+            self.matcher.start(&set);
+            self.current = Some(set);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Implementation of the query lexer.
+//!
+//! The lexer is a simple hand written lexer that split a query into [`Token`] for the
+//! [`QueryParser`](super::QueryParser). Keywords are not resolve here, they are emitted as
+//! [`Token::Word`] and the parser decide what they mean.
+//!
+//! Every token come with a [`Span`] so error can point at where in the query they happen.
+
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::{QueryError, QueryErrorKind};
+
+/// Byte range of a token in the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    /// Byte offset of the start of the token.
+    pub start: usize,
+    /// Byte offset right after the end of the token.
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a new span.
+    #[must_use]
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+/// Enum containing variant that can be emitted by the lexer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// End of input token. Named EOF for convention.
+    Eof,
+
+    /// Token for an opening parenthesis `(` use for grouping.
+    OpenParen,
+    /// Token for a closeing parenthesis `)` use for grouping.
+    CloseParen,
+
+    /// Token for a bare word.
+    ///
+    /// Keywords, numbers and unquoted values are all words. A word can contain `-` and `'` as
+    /// long as it is not the first character so name like `half-beast` or `bear's` stay as 1
+    /// word.
+    Word(String),
+    /// Token for a string surrounded by `"`.
+    Str(String),
+    /// Token for a regex literal surrounded by `/`. Only allowed right after a colon.
+    Regex(String),
+
+    /// Token for the not operator `!`.
+    Not,
+
+    /// Token for the colon use to seprate between keyword and argument.
+    Colon,
+    /// Token for the tilde use to separate keyword and argument for similarity matching.
+    Tilde,
+
+    /// Token for the equal sign use to separate keyword and argument.
+    Equal,
+    /// Token for the greater than sign use to separate keyword and argument.
+    Greater,
+    /// Token for the greater than ad equal sign use to separate keyword and argument.
+    GreaterEq,
+    /// Token for the lesser than sign use to separate keyword and argument.
+    Less,
+    /// Token for the lesser than and equal sign use to separate keyword and argument.
+    LessEq,
+
+    /// Token for the plus sign use in expression.
+    Plus,
+    /// Token for the minus sign use in expression.
+    Minus,
+    /// Token for the star or multiply sign use in expression.
+    Star,
+    /// Token for the slash or divide sign use in expression.
+    Slash,
+    /// Token for the dot use to access cost component in expression.
+    Dot,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Eof => write!(f, "end of query"),
+            Token::OpenParen => write!(f, "`(`"),
+            Token::CloseParen => write!(f, "`)`"),
+            Token::Word(w) => write!(f, "`{w}`"),
+            Token::Str(s) => write!(f, "\"{s}\""),
+            Token::Regex(r) => write!(f, "/{r}/"),
+            Token::Not => write!(f, "`!`"),
+            Token::Colon => write!(f, "`:`"),
+            Token::Tilde => write!(f, "`~`"),
+            Token::Equal => write!(f, "`=`"),
+            Token::Greater => write!(f, "`>`"),
+            Token::GreaterEq => write!(f, "`>=`"),
+            Token::Less => write!(f, "`<`"),
+            Token::LessEq => write!(f, "`<=`"),
+            Token::Plus => write!(f, "`+`"),
+            Token::Minus => write!(f, "`-`"),
+            Token::Star => write!(f, "`*`"),
+            Token::Slash => write!(f, "`/`"),
+            Token::Dot => write!(f, "`.`"),
+        }
+    }
+}
+
+/// Tokenize a given query. Fail on unrecognized character or unterminated string and regex.
+///
+/// Strings can contain any character, `\"` and `\\` can be use to escape a quote or a backslash.
+/// Regex can use `\/` to escape a slash.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::query::{tokenize, Span, Token};
+///
+/// assert_eq!(
+///     tokenize("a>=2").unwrap(),
+///     vec![
+///         (Token::Word("a".to_string()), Span::new(0, 1)),
+///         (Token::GreaterEq, Span::new(1, 3)),
+///         (Token::Word("2".to_string()), Span::new(3, 4)),
+///         (Token::Eof, Span::new(4, 4)),
+///     ]
+/// );
+///
+/// assert_eq!(
+///     tokenize(r#""Mr. \"Egg\"""#).unwrap()[0].0,
+///     Token::Str(r#"Mr. "Egg""#.to_string())
+/// );
+/// ```
+pub fn tokenize(query: &str) -> Result<Vec<(Token, Span)>, QueryError> {
+    let mut tokens = vec![];
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+
+            '"' => match take_until(&mut chars, '"', &['"', '\\']) {
+                Some(str) => Token::Str(str),
+                None => {
+                    return Err(QueryError::new(
+                        QueryErrorKind::UnterminatedString,
+                        Span::new(start, query.len()),
+                        query,
+                    ))
+                }
+            },
+
+            ':' => {
+                // regex literal have to be right after the colon so `/` can still be use for
+                // division
+                if let Some((regex_start, _)) = chars.next_if(|&(_, c)| c == '/') {
+                    let Some(regex) = take_until(&mut chars, '/', &['/']) else {
+                        return Err(QueryError::new(
+                            QueryErrorKind::UnterminatedRegex,
+                            Span::new(regex_start, query.len()),
+                            query,
+                        ));
+                    };
+                    tokens.push((Token::Colon, Span::new(start, regex_start)));
+                    tokens.push((
+                        Token::Regex(regex),
+                        Span::new(regex_start, end(&mut chars, query)),
+                    ));
+                    continue;
+                }
+                Token::Colon
+            }
+
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '!' => Token::Not,
+            '~' => Token::Tilde,
+            '=' => Token::Equal,
+            '>' if chars.next_if(|&(_, c)| c == '=').is_some() => Token::GreaterEq,
+            '>' => Token::Greater,
+            '<' if chars.next_if(|&(_, c)| c == '=').is_some() => Token::LessEq,
+            '<' => Token::Less,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '.' => Token::Dot,
+
+            c if is_word(c) => {
+                let mut word = String::from(c);
+                while let Some((_, c)) =
+                    chars.next_if(|&(_, c)| is_word(c) || c == '-' || c == '\'')
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+
+            c => {
+                return Err(QueryError::new(
+                    QueryErrorKind::UnexpectedChar(c),
+                    Span::new(start, start + c.len_utf8()),
+                    query,
+                ))
+            }
+        };
+
+        tokens.push((token, Span::new(start, end(&mut chars, query))));
+    }
+
+    tokens.push((Token::Eof, Span::new(query.len(), query.len())));
+
+    Ok(tokens)
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Get the byte offset of the next character.
+fn end(chars: &mut Peekable<CharIndices>, query: &str) -> usize {
+    chars.peek().map_or(query.len(), |&(i, _)| i)
+}
+
+/// Take every character until the closing character. `\` can be use to escape any of the
+/// `escapes` character, other `\` are kept as is.
+///
+/// Return [`None`] if the closing character is never found.
+fn take_until(chars: &mut Peekable<CharIndices>, close: char, escapes: &[char]) -> Option<String> {
+    let mut out = String::new();
+
+    loop {
+        match chars.next()?.1 {
+            '\\' if chars.peek().is_some_and(|(_, c)| escapes.contains(c)) => {
+                out.push(chars.next()?.1);
+            }
+            c if c == close => return Some(out),
+            c => out.push(c),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Implementation of the query plan optimizer.
+//!
+//! Before compiling the filters [`QueryBuilder::query`](super::QueryBuilder::query) run them
+//! through [`optimize`]. The optimizer:
+//!
+//! - Flatten nested logical filters, `and` get split into separate filters, double `not` are
+//!   remove and `not` over an `or` is turn into multiple `not` with De Morgan's law.
+//! - Detect filters that contradict each other like `a>5 a<2` so we don't have to look at any
+//!   card.
+//! - Reorder the filters so cheap filters that remove a lot of card like the set or rarity are
+//!   run first and expensive one like regex are run last.
+
+use crate::{Rarity, SetCode, Temple};
+
+use super::{Filters, QueryOrder, Shorthand, ToFilter};
+
+/// Optimize a list of filters for querying.
+///
+/// Return [`None`] if the filters contradict each other so no card can ever match. The filters
+/// return match the exact same cards as the original filters but in a faster order.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+///
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("s:airborne r:rare").unwrap();
+/// let plan = query::optimize(filters).unwrap();
+///
+/// // the rarity is check first since it is cheaper
+/// assert!(matches!(plan[0], Filters::Rarity(_)));
+///
+/// // no card can have more than 5 attack and less than 2
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("a>5 a<2").unwrap();
+/// assert!(query::optimize(filters).is_none());
+/// ```
+#[must_use]
+pub fn optimize<E, C, F>(filters: Vec<Filters<E, C, F>>) -> Option<Vec<Filters<E, C, F>>>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    let mut out = vec![];
+    for f in filters {
+        flatten(f, &mut out);
+    }
+
+    if contradict(&out) {
+        return None;
+    }
+
+    // stable sort so filters with the same cost keep the order the user wrote them
+    sort_by_cost(&mut out);
+
+    Some(out)
+}
+
+/// Split a filter into filters that all have to match.
+pub(super) fn flatten<E, C, F>(filter: Filters<E, C, F>, out: &mut Vec<Filters<E, C, F>>)
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    match filter {
+        Filters::And(a, b) => {
+            flatten(*a, out);
+            flatten(*b, out);
+        }
+        Filters::Not(f) => match *f {
+            Filters::Not(f) => flatten(*f, out),
+            Filters::Or(a, b) => {
+                flatten(Filters::Not(a), out);
+                flatten(Filters::Not(b), out);
+            }
+            f => out.push(Filters::Not(Box::new(f))),
+        },
+        f => out.push(f),
+    }
+}
+
+/// Inclusive range of value a stat can be in.
+struct Range {
+    min: i128,
+    max: i128,
+}
+
+impl Range {
+    fn new() -> Self {
+        Range {
+            min: i128::MIN,
+            max: i128::MAX,
+        }
+    }
+
+    fn narrow(&mut self, ord: &QueryOrder, value: i128) {
+        match ord {
+            QueryOrder::Greater => self.min = self.min.max(value + 1),
+            QueryOrder::GreaterEqual => self.min = self.min.max(value),
+            QueryOrder::Equal => {
+                self.min = self.min.max(value);
+                self.max = self.max.min(value);
+            }
+            QueryOrder::LessEqual => self.max = self.max.min(value),
+            QueryOrder::Less => self.max = self.max.min(value - 1),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+}
+
+/// Check if the filters can never all match the same card.
+fn contradict<E, C, F>(filters: &[Filters<E, C, F>]) -> bool
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    let mut attack = Range::new();
+    let mut health = Range::new();
+    // sigil count can never be negative
+    let mut sigils = Range {
+        min: 0,
+        ..Range::new()
+    };
+
+    let mut set: Option<&SetCode> = None;
+    let mut rarity: Option<&Rarity> = None;
+    let mut temple: Option<&Temple> = None;
+
+    for f in filters {
+        let conflict = match f {
+            Filters::Attack(o, a) => {
+                attack.narrow(o, i128::try_from(*a).unwrap_or_default());
+                attack.is_empty()
+            }
+            Filters::Health(o, h) => {
+                health.narrow(o, i128::try_from(*h).unwrap_or_default());
+                health.is_empty()
+            }
+            Filters::SigilCount(o, c) => {
+                sigils.narrow(o, i128::try_from(*c).unwrap_or(i128::MAX));
+                sigils.is_empty()
+            }
+            // set code are case insensitive so `e:aug` still match the `Aug` set
+            Filters::Set(s) => {
+                if let Some(seen) = set {
+                    !seen.eq_ignore_ascii_case(s)
+                } else {
+                    set = Some(s);
+                    false
+                }
+            }
+            Filters::Rarity(r) => conflict(&mut rarity, r),
+            Filters::Temple(t) => conflict(&mut temple, t),
+            _ => false,
+        };
+
+        if conflict {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Return true if a different value was already seen.
+fn conflict<'a, T: PartialEq>(seen: &mut Option<&'a T>, value: &'a T) -> bool {
+    if let Some(s) = seen {
+        *s != value
+    } else {
+        *seen = Some(value);
+        false
+    }
+}
+
+/// Rough estimate of how expensive a filter is to run on a card, lower is cheaper.
+///
+/// Filters that only compare a field are the cheapest, then string searching, then the filters
+/// that need to look up data prepared from the set and finally regex and the extra filters since
+/// we have no idea what they do.
+fn cost<E, C, F>(filter: &Filters<E, C, F>) -> u32
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    match filter {
+        Filters::Set(_) | Filters::Rarity(_) | Filters::Temple(_) | Filters::Unique(_) => 0,
+        Filters::Attack(..)
+        | Filters::Health(..)
+        | Filters::SigilCount(..)
+        | Filters::SpAtk(_)
+        | Filters::Is(Shorthand::Free | Shorthand::Split | Shorthand::MultiTemple) => 1,
+        Filters::Costs(_) | Filters::Traits(_) | Filters::StrAtk(_) | Filters::Expr(..) => 2,
+
+        Filters::Name(_)
+        | Filters::Description(_)
+        | Filters::Tribe(_)
+        | Filters::Sigil(_)
+        | Filters::Related(_)
+        | Filters::Is(Shorthand::Evolves) => 4,
+
+        Filters::SigilDescription(_) | Filters::RelatedOf(_) | Filters::Is(Shorthand::Token) => 6,
+
+        #[cfg(feature = "regex")]
+        Filters::NameRegex(_) | Filters::DescriptionRegex(_) | Filters::TribeRegex(_) => 8,
+        Filters::Extra(_) => 10,
+
+        Filters::And(a, b) | Filters::Or(a, b) => cost(a) + cost(b),
+        Filters::Not(f) => cost(f),
+
+        Filters::McGuffin(..) | Filters::Cake(..) => unreachable!(),
+    }
+}
+
+/// Split a filter into filters where any of them have to match.
+pub(super) fn flatten_or<E, C, F>(filter: Filters<E, C, F>, out: &mut Vec<Filters<E, C, F>>)
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    match filter {
+        Filters::Or(a, b) => {
+            flatten_or(*a, out);
+            flatten_or(*b, out);
+        }
+        Filters::Not(f) => match *f {
+            Filters::Not(f) => flatten_or(*f, out),
+            f => out.push(Filters::Not(Box::new(f))),
+        },
+        f => out.push(f),
+    }
+}
+
+/// Sort filters that are going to be run together from cheapest to most expensive.
+pub(super) fn sort_by_cost<E, C, F>(filters: &mut [Filters<E, C, F>])
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    filters.sort_by_key(cost);
+}
@@ -0,0 +1,907 @@
+//! Implementation of the query syntax parser.
+//!
+//! The parser is a simple recursive descent parser that turn the [`Token`] from the lexer directly
+//! into [`Filters`] to be put into a [`QueryBuilder`](super::QueryBuilder). The syntax is
+//! documented on [`QueryParser`].
+
+use std::marker::PhantomData;
+
+use crate::{Costs, Mox, Rarity, SetCode, SpAtk, Temple, Traits, TraitsFlag};
+
+use super::{
+    tokenize, Expr, Filters, QueryError, QueryErrorKind, QueryOrder, Shorthand, Span, Stat,
+    ToFilter, Token, Unique,
+};
+
+/// Separator between a keyword and its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// The colon separator, `name:x`.
+    Colon,
+    /// The tilde separator, `name~x`. Use for similarity matching.
+    Tilde,
+}
+
+/// How deep groups and negative numbers can be nested before the parser give up.
+///
+/// The parser is recursive so without a limit a query like `((((...))))` could overflow the stack.
+pub const MAX_DEPTH: usize = 64;
+
+/// Function that convert the value of an extra keyword into an extra filter.
+pub type KeywordFn<F> = Box<dyn Fn(&str) -> Result<F, String> + Send + Sync>;
+
+struct ExtraKeyword<F> {
+    aliases: Vec<String>,
+    separator: Separator,
+    func: KeywordFn<F>,
+}
+
+/// Parser for the query syntax.
+///
+/// A query is a list of keywords separated by spaces, every keyword have to match for a card to be
+/// in the result. Keywords are case insensitive and most of them have a short alias. Like
+/// [Scryfall](https://scryfall.com/docs/syntax), a bare word or a quoted string without a keyword
+/// search by name.
+///
+/// Keywords can be negated with `!` or `-` and combine with `and` and `or`, `and` bind tighter
+/// than `or` and the spaces between keywords work like an `and`. Parentheses can be use to group
+/// keywords together, a group can also be negated like `-(a>1 or h>1)`.
+///
+/// | Keyword                | Value                                                   |
+/// | ---------------------- | ------------------------------------------------------- |
+/// | `name`, `n`            | Part of the name or a `/regex/`                         |
+/// | `description`, `d`     | Part of the description or a `/regex/`                  |
+/// | `set`, `e`             | A set code                                              |
+/// | `rarity`, `r`          | `side`, `common`, `uncommon`, `rare` or `unique`        |
+/// | `temple`, `tp`         | `beast`, `undead`, `tech`, `magick`, `fool`, `artistry` |
+/// | `tribe`, `tb`          | Part of the tribe or a `/regex/`                        |
+/// | `sigil`, `s`           | Part of a sigil name                                    |
+/// | `sigiltext`, `st`      | Part of a sigil description                             |
+/// | `spatk`, `sp`          | `mox`, `green`, `mirror`, `ant`, `bone`, `bell`, `card` |
+/// | `cost`, `c`            | A cost like `2b` or `1b3o`                              |
+/// | `trait`, `tr`          | A trait like `conductive` or `terrain`                  |
+/// | `related`, `rel`       | Part of a related card name or `any`                    |
+/// | `relatedof`, `rof`     | A name or a group of keywords like `rof:(s:fledgling)`  |
+/// | `is`                   | `vanilla`, `rare`, a trait flag or a [`Shorthand`]      |
+/// | `unique`               | `names`, `prints` or `all`, see [`Unique`]              |
+///
+/// Stats are compare using `:`, `=`, `>`, `<`, `>=` and `<=` instead. Both side of the comparison
+/// can be an expression using `+`, `-`, `*` and `/` with `attack` (`a`), `health` (`h`), `sigils`
+/// (`sc`) and `cost.blood`, `cost.bone`, `cost.energy` or `cost.mox`. Note that `-` have to be
+/// surrounded by space as it can be part of a word, a `-` stuck to the next keyword like in
+/// `a>1 -s:airborne` negate that keyword instead.
+///
+/// Here a simple top down view of the grammar in pesudo EBNF
+///
+/// ```ebnf
+/// (*
+///     Uppercase mean they are emit by the tokenizer, they are usually too long and you can infer
+///     what they mean.
+/// *)
+///
+/// program = [ or ]
+///
+/// or = and { "or" and }
+/// and = not { [ "and" ] not }
+/// not = [ "!" | "-" ] keyword
+/// keyword = "(" or ")" | str_keyword | cmp_keyword | WORD | STR
+///
+/// str_keyword = WORD ( ":" | "~" ) ( WORD | STR | REGEX | "(" or ")" )
+/// cmp_keyword = math ( ":" | "=" | ">" | "<" | ">=" | "<=" ) math
+///
+/// math = term { ( "+" | "-" ) term }
+/// term = factor { ( "*" | "/" ) factor }
+/// factor = NUM | STAT | "cost" "." COST_TYPE | "-" factor
+/// ```
+///
+/// Groups and negative numbers can only be nested [`MAX_DEPTH`] deep, past that the query is
+/// rejected with [`QueryErrorKind::TooDeep`].
+///
+/// Frontend can register extra keywords that map to [`Filters::Extra`] using
+/// [`QueryParser::keyword`]. Extra keywords are check before the built in keywords so they can
+/// also be use to override them.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+/// use magpie_engine::query::{QueryErrorKind, Separator};
+///
+/// #[derive(Debug, Clone)]
+/// struct Similar(String);
+///
+/// impl ToFilter<(), ()> for Similar {
+///     fn to_fn(self) -> FilterFn<(), ()> {
+///         Box::new(move |c| c.name.starts_with(&self.0))
+///     }
+/// }
+///
+/// let parser = QueryParser::new()
+///     .keyword(&["name", "n"], Separator::Tilde, |v| Ok(Similar(v.to_owned())));
+///
+/// let filters: Vec<Filters<(), (), Similar>> = parser.parse("n~stoat a>=1").unwrap();
+///
+/// assert!(matches!(filters[0], Filters::Extra(Similar(_))));
+/// assert!(matches!(filters[1], Filters::Attack(QueryOrder::GreaterEqual, 1)));
+///
+/// // spaces bind tighter than `or` like an `and` would
+/// let parser = QueryParser::<(), (), ()>::new();
+/// let text = |q| query::to_query_string(&parser.parse(q).unwrap()).unwrap();
+///
+/// assert_eq!(text("a>1 s:x or h>2"), text("(a>1 and s:x) or h>2"));
+///
+/// let query = format!("{}a>1{}", "(".repeat(100), ")".repeat(100));
+/// let err = QueryParser::<(), (), ()>::new().parse(&query).unwrap_err();
+///
+/// assert!(matches!(err.kind, QueryErrorKind::TooDeep));
+/// ```
+pub struct QueryParser<E, C, F> {
+    extra: Vec<ExtraKeyword<F>>,
+    phantom: PhantomData<fn() -> (E, C)>,
+}
+
+impl<E, C, F> Default for QueryParser<E, C, F> {
+    fn default() -> Self {
+        Self {
+            extra: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Parse a query into a list of [`Filters`] using the built in keywords.
+///
+/// Use [`QueryParser`] if you want to register extra keywords.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+///
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("a>=3 h<3 s:airborne").unwrap();
+///
+/// assert_eq!(filters.len(), 3);
+/// assert!(matches!(filters[0], Filters::Attack(QueryOrder::GreaterEqual, 3)));
+///
+/// // bare words search by name and `-` negate a keyword
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("stoat -s:airborne").unwrap();
+///
+/// assert!(matches!(&filters[0], Filters::Name(n) if n == "stoat"));
+/// assert!(matches!(filters[1], Filters::Not(_)));
+///
+/// // punctuation stay in the bare name
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("Mr. Egg bear's").unwrap();
+///
+/// assert!(matches!(&filters[0], Filters::Name(n) if n == "Mr."));
+/// assert!(matches!(&filters[1], Filters::Name(n) if n == "Egg"));
+/// assert!(matches!(&filters[2], Filters::Name(n) if n == "bear's"));
+///
+/// // a `-` after a comparison start a new keyword when it is follow by one
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("a>1 -s:airborne").unwrap();
+///
+/// assert!(matches!(filters[0], Filters::Attack(QueryOrder::Greater, 1)));
+/// assert!(matches!(filters[1], Filters::Not(_)));
+///
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("a>1 -1<h").unwrap();
+///
+/// assert!(matches!(filters[0], Filters::Attack(QueryOrder::Greater, 1)));
+/// assert!(matches!(filters[1], Filters::Expr(Expr::Num(-1), QueryOrder::Less, _)));
+///
+/// // otherwise it is still a subtraction
+/// let filters: Vec<Filters<(), (), ()>> = query::parse("a>h - 1").unwrap();
+///
+/// assert_eq!(filters.len(), 1);
+/// ```
+pub fn parse<E, C, F>(query: &str) -> Result<Vec<Filters<E, C, F>>, QueryError>
+where
+    E: Clone,
+    C: Clone + PartialEq + Default,
+    F: ToFilter<E, C>,
+{
+    QueryParser::new().parse(query)
+}
+
+type ParseRes<E, C, F> = Result<Filters<E, C, F>, QueryError>;
+
+impl<E, C, F> QueryParser<E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq + Default,
+    F: ToFilter<E, C>,
+{
+    /// Create a new parser with only the built in keywords.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an extra keyword.
+    ///
+    /// The keyword is match case insensitively against `aliases` and is only use with the given
+    /// `separator`. The function receive the value of the keyword and return the extra filter or
+    /// an error message.
+    #[must_use]
+    pub fn keyword(
+        mut self,
+        aliases: &[&str],
+        separator: Separator,
+        func: impl Fn(&str) -> Result<F, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.extra.push(ExtraKeyword {
+            aliases: aliases.iter().map(|a| a.to_lowercase()).collect(),
+            separator,
+            func: Box::new(func),
+        });
+        self
+    }
+
+    /// Parse a query into a list of [`Filters`].
+    pub fn parse(&self, query: &str) -> Result<Vec<Filters<E, C, F>>, QueryError> {
+        let mut tokens = tokenize(query)?;
+        tokens.reverse();
+
+        let mut state = ParseState {
+            parser: self,
+            query,
+            tokens,
+            last: Span::default(),
+            start: 0,
+            depth: 0,
+        };
+
+        if !state.not_eof() {
+            return Ok(vec![]);
+        }
+
+        let mut filter = state.parse()?;
+
+        // only a stray `)` can be left over
+        if state.not_eof() {
+            state.start = state.curr_span().start;
+            let tk = state.next();
+            return Err(state.error(QueryErrorKind::Expected("a keyword", tk)));
+        }
+
+        // split the top level `and` back into a list so each keyword is its own filter
+        let mut filters = vec![];
+        while let Filters::And(left, right) = filter {
+            filters.push(*right);
+            filter = *left;
+        }
+        filters.push(filter);
+        filters.reverse();
+
+        Ok(filters)
+    }
+
+    fn is_extra(&self, word: &str) -> bool {
+        self.extra
+            .iter()
+            .any(|k| k.aliases.iter().any(|a| a == word))
+    }
+
+    fn extra_keyword(&self, word: &str, separator: Separator) -> Option<&KeywordFn<F>> {
+        self.extra
+            .iter()
+            .find(|k| k.separator == separator && k.aliases.iter().any(|a| a == word))
+            .map(|k| &k.func)
+    }
+}
+
+/// The built in keywords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    Name,
+    Desc,
+    Set,
+    Rarity,
+    Temple,
+    Tribe,
+    Attack,
+    Health,
+    Sigil,
+    SigilText,
+    SigilCount,
+    SpAtk,
+    Costs,
+    Trait,
+    Related,
+    RelatedOf,
+    Is,
+    Unique,
+}
+
+impl Keyword {
+    fn from_word(word: &str) -> Option<Self> {
+        Some(match word {
+            "name" | "n" => Keyword::Name,
+            "description" | "d" => Keyword::Desc,
+            "set" | "e" => Keyword::Set,
+            "rarity" | "r" => Keyword::Rarity,
+            "temple" | "tp" => Keyword::Temple,
+            "tribe" | "tb" => Keyword::Tribe,
+            "attack" | "a" => Keyword::Attack,
+            "health" | "h" => Keyword::Health,
+            "sigil" | "s" => Keyword::Sigil,
+            "sigiltext" | "st" => Keyword::SigilText,
+            "sigils" | "sc" => Keyword::SigilCount,
+            "spatk" | "sp" => Keyword::SpAtk,
+            "cost" | "c" => Keyword::Costs,
+            "trait" | "tr" => Keyword::Trait,
+            "related" | "rel" => Keyword::Related,
+            "relatedof" | "rof" => Keyword::RelatedOf,
+            "is" => Keyword::Is,
+            "unique" => Keyword::Unique,
+            _ => return None,
+        })
+    }
+}
+
+struct ParseState<'p, 'q, E, C, F> {
+    parser: &'p QueryParser<E, C, F>,
+    query: &'q str,
+    tokens: Vec<(Token, Span)>,
+    /// Span of the last token taken.
+    last: Span,
+    /// Start of the keyword currently being parse, use to give error some context.
+    start: usize,
+    /// How many groups and negative numbers we are currently in.
+    depth: usize,
+}
+
+// Helper to map a value to a bit flag filter
+macro_rules! map_value {
+    ($self:ident, $value:ident => $type:ident($name:literal), $($pat:pat => $variant:ident),*) => {
+        match $value.to_lowercase().as_str() {
+            $($pat => Ok(Filters::$type($type::$variant.into())),)*
+            _ => Err($self.error(QueryErrorKind::InvalidValue($name, $value)))
+        }
+    };
+}
+
+impl<E, C, F> ParseState<'_, '_, E, C, F>
+where
+    E: Clone,
+    C: Clone + PartialEq + Default,
+    F: ToFilter<E, C>,
+{
+    fn parse(&mut self) -> ParseRes<E, C, F> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> ParseRes<E, C, F> {
+        let mut left = self.parse_and()?;
+
+        while matches!(self.curr(), Token::Word(w) if w.eq_ignore_ascii_case("or")) {
+            self.next();
+            let right = self.parse_and()?;
+            left = Filters::Or(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> ParseRes<E, C, F> {
+        let mut left = self.parse_not()?;
+
+        loop {
+            match self.curr() {
+                Token::Word(w) if w.eq_ignore_ascii_case("and") => {
+                    self.next();
+                }
+                Token::Word(w) if w.eq_ignore_ascii_case("or") => break,
+                Token::CloseParen | Token::Eof => break,
+                // anything else start another keyword that is implicitly and together
+                _ => {}
+            }
+
+            let right = self.parse_not()?;
+            left = Filters::And(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> ParseRes<E, C, F> {
+        let negate = match self.curr() {
+            Token::Not => true,
+            // `-1<a` is a negative number and not a negation
+            Token::Minus => {
+                !matches!(self.peek(), Some(Token::Word(w)) if w.parse::<isize>().is_ok())
+            }
+            _ => false,
+        };
+
+        if !negate {
+            return self.parse_keyword();
+        }
+        self.next();
+        Ok(Filters::Not(Box::new(self.parse_keyword()?)))
+    }
+
+    fn parse_keyword(&mut self) -> ParseRes<E, C, F> {
+        self.start = self.curr_span().start;
+
+        let word = match self.curr() {
+            Token::OpenParen => return self.parse_group(),
+            Token::Minus => return self.parse_cmp_keyword(),
+            Token::Str(_) => return self.parse_name(),
+            Token::Word(w) => w.to_lowercase(),
+            Token::Dot => {
+                let tk = self.next();
+                return Err(self.error(QueryErrorKind::Expected(
+                    "a keyword (quote name with punctuation like `\"Mr. Egg\"`)",
+                    tk,
+                )));
+            }
+            _ => {
+                let tk = self.next();
+                return Err(self.error(QueryErrorKind::Expected("a keyword", tk)));
+            }
+        };
+
+        let keyword = Keyword::from_word(&word);
+        let is_stat = word.parse::<isize>().is_ok()
+            || matches!(
+                keyword,
+                Some(Keyword::Attack | Keyword::Health | Keyword::SigilCount)
+            );
+
+        match self.peek() {
+            // `a -s:airborne` is a name search and a negated keyword, not a subtraction
+            Some(Token::Minus) if is_stat && self.starts_term(1) => self.parse_name(),
+            Some(
+                Token::Colon
+                | Token::Equal
+                | Token::Greater
+                | Token::GreaterEq
+                | Token::Less
+                | Token::LessEq
+                | Token::Plus
+                | Token::Minus
+                | Token::Star
+                | Token::Slash,
+            ) if is_stat => self.parse_cmp_keyword(),
+            // cost access in expression like `cost.blood`
+            Some(Token::Dot) if keyword == Some(Keyword::Costs) => self.parse_cmp_keyword(),
+            Some(Token::Colon | Token::Tilde) => match keyword {
+                Some(kw) => self.parse_str_keyword(&word, Some(kw)),
+                None if self.parser.is_extra(&word) => self.parse_str_keyword(&word, None),
+                None => {
+                    let tk = self.next();
+                    Err(self.error(QueryErrorKind::InvalidKeyword(tk)))
+                }
+            },
+            // anything else is a bare word so search by name like scryfall
+            _ => self.parse_name(),
+        }
+    }
+
+    fn parse_group(&mut self) -> ParseRes<E, C, F> {
+        self.next();
+
+        self.nested(|this| {
+            let group = this.parse()?;
+            this.expect_token(&Token::CloseParen, "`)`")?;
+            Ok(group)
+        })
+    }
+
+    fn parse_name(&mut self) -> ParseRes<E, C, F> {
+        match self.next() {
+            Token::Word(mut name) => {
+                // keep the punctuation in bare name like `Mr.Egg` or `Mr.`
+                while self.curr_span().start == self.last.end {
+                    match self.curr() {
+                        Token::Dot => name.push('.'),
+                        Token::Word(w) => name.push_str(w),
+                        _ => break,
+                    }
+                    self.next();
+                }
+                Ok(Filters::Name(name))
+            }
+            Token::Str(name) => Ok(Filters::Name(name)),
+            tk => Err(self.error(QueryErrorKind::Expected("a name", tk))),
+        }
+    }
+
+    fn parse_str_keyword(&mut self, word: &str, keyword: Option<Keyword>) -> ParseRes<E, C, F> {
+        self.next();
+
+        let separator = match self.next() {
+            Token::Colon => Separator::Colon,
+            Token::Tilde => Separator::Tilde,
+            tk => return Err(self.error(QueryErrorKind::Expected("`:`", tk))),
+        };
+
+        if let Some(func) = self.parser.extra_keyword(word, separator) {
+            return match self.next() {
+                Token::Word(v) | Token::Str(v) => func(&v)
+                    .map(Filters::Extra)
+                    .map_err(|e| self.error(QueryErrorKind::Extra(e))),
+                tk => Err(self.error(QueryErrorKind::Expected("a value", tk))),
+            };
+        }
+
+        let (Some(keyword), Separator::Colon) = (keyword, separator) else {
+            return Err(self.error(QueryErrorKind::Expected("`:`", Token::Tilde)));
+        };
+
+        // related of can take a whole group like `rof:(s:fledgling)`
+        if keyword == Keyword::RelatedOf && self.curr() == &Token::OpenParen {
+            return Ok(Filters::RelatedOf(Box::new(self.parse_group()?)));
+        }
+
+        let value = match self.next() {
+            Token::Word(v) | Token::Str(v) => v,
+            Token::Regex(regex) => {
+                return regex_keyword(keyword, regex).map_err(|k| self.error(k));
+            }
+            tk => return Err(self.error(QueryErrorKind::Expected("a value", tk))),
+        };
+
+        match keyword {
+            Keyword::Name => Ok(Filters::Name(value)),
+            Keyword::Desc => Ok(Filters::Description(value)),
+            Keyword::Set => match SetCode::new(&value) {
+                Some(code) => Ok(Filters::Set(code)),
+                None => Err(self.error(QueryErrorKind::InvalidValue("set code", value))),
+            },
+            Keyword::Rarity => map_value! {
+                self, value => Rarity("rarity"),
+                "side" | "s" => SIDE,
+                "common" | "c" => COMMON,
+                "uncommon" | "u" => UNCOMMON,
+                "rare" | "r" => RARE,
+                "unique" | "n" => UNIQUE
+            },
+            Keyword::Temple => map_value! {
+                self, value => Temple("temple"),
+                "beast" | "b" => BEAST,
+                "undead" | "u" => UNDEAD,
+                "technology" | "tech" | "t" => TECH,
+                "magick" | "m" => MAGICK,
+                "fool" | "f" => FOOL,
+                "artistry" | "a" => ARTISTRY
+            },
+            Keyword::Tribe => Ok(Filters::Tribe(Some(value))),
+            Keyword::Sigil => Ok(Filters::Sigil(value)),
+            Keyword::SigilText => Ok(Filters::SigilDescription(value)),
+            Keyword::SpAtk => map_value! {
+                self, value => SpAtk("special attack"),
+                "mox" => MOX,
+                "green" => GREEN_MOX,
+                "mirror" => MIRROR,
+                "ant" => ANT,
+                "bone" => BONE,
+                "bell" => BELL,
+                "card" => CARD
+            },
+            Keyword::Costs => parse_costs(value)
+                .map(|c| Filters::Costs(Some(c)))
+                .map_err(|k| self.error(k)),
+            Keyword::Trait => Ok(Filters::Traits(Some(parse_traits(&value)))),
+            Keyword::Related if value.eq_ignore_ascii_case("any") => Ok(Filters::Related(None)),
+            Keyword::Related => Ok(Filters::Related(Some(value))),
+            Keyword::RelatedOf => Ok(Filters::RelatedOf(Box::new(Filters::Name(value)))),
+            Keyword::Is => Ok(match value.to_lowercase().as_str() {
+                "free" => Filters::Is(Shorthand::Free),
+                "vanilla" => Filters::SigilCount(QueryOrder::Equal, 0),
+                "token" => Filters::Is(Shorthand::Token),
+                "rare" => Filters::Rarity(Rarity::RARE),
+                "conductive" => Filters::Traits(Some(Traits::with_flags(TraitsFlag::CONDUCTIVE))),
+                "terrain" => Filters::Traits(Some(Traits::with_flags(TraitsFlag::TERRAIN))),
+                "banned" | "ban" => Filters::Traits(Some(Traits::with_flags(TraitsFlag::BAN))),
+                "hard" => Filters::Traits(Some(Traits::with_flags(TraitsFlag::HARD))),
+                "split" => Filters::Is(Shorthand::Split),
+                "evolves" | "evolve" => Filters::Is(Shorthand::Evolves),
+                "multitemple" => Filters::Is(Shorthand::MultiTemple),
+                _ => return Err(self.error(QueryErrorKind::InvalidValue("is", value))),
+            }),
+            Keyword::Unique => map_value! {
+                self, value => Unique("unique"),
+                "names" | "name" | "cards" => Names,
+                "prints" | "print" => Prints,
+                "all" => All
+            },
+            Keyword::Attack | Keyword::Health | Keyword::SigilCount => unreachable!(),
+        }
+    }
+
+    fn parse_cmp_keyword(&mut self) -> ParseRes<E, C, F> {
+        let left = self.parse_math()?;
+
+        let cmp = match self.next() {
+            Token::Colon | Token::Equal => QueryOrder::Equal,
+            Token::Greater => QueryOrder::Greater,
+            Token::GreaterEq => QueryOrder::GreaterEqual,
+            Token::Less => QueryOrder::Less,
+            Token::LessEq => QueryOrder::LessEqual,
+
+            tk => return Err(self.error(QueryErrorKind::Expected("a comparison like `>=`", tk))),
+        };
+
+        let right = self.parse_math()?;
+
+        // keep simple comparasion as their own filter so they display nicer
+        Ok(match (left, right) {
+            (Expr::Stat(Stat::Attack), Expr::Num(num)) => Filters::Attack(cmp, num),
+            (Expr::Stat(Stat::Health), Expr::Num(num)) => Filters::Health(cmp, num),
+            (Expr::Stat(Stat::SigilCount), Expr::Num(num)) if num >= 0 => {
+                Filters::SigilCount(cmp, num.unsigned_abs())
+            }
+            (left, right) => Filters::Expr(left, cmp, right),
+        })
+    }
+
+    fn parse_math(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_term()?;
+
+        loop {
+            left = match self.curr() {
+                Token::Plus => {
+                    self.next();
+                    Expr::Add(Box::new(left), Box::new(self.parse_term()?))
+                }
+                // the `-` in `a>1 -s:airborne` is negating the next keyword
+                Token::Minus if !self.starts_term(0) => {
+                    self.next();
+                    Expr::Sub(Box::new(left), Box::new(self.parse_term()?))
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_factor()?;
+
+        loop {
+            left = match self.curr() {
+                Token::Star => {
+                    self.next();
+                    Expr::Mul(Box::new(left), Box::new(self.parse_factor()?))
+                }
+                Token::Slash => {
+                    self.next();
+                    Expr::Div(Box::new(left), Box::new(self.parse_factor()?))
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, QueryError> {
+        let word = match self.next() {
+            Token::Word(w) => w,
+            Token::Minus => {
+                return Ok(match self.nested(Self::parse_factor)? {
+                    Expr::Num(num) => Expr::Num(-num),
+                    expr => Expr::Sub(Box::new(Expr::Num(0)), Box::new(expr)),
+                })
+            }
+            tk => return Err(self.error(QueryErrorKind::Expected("a number", tk))),
+        };
+
+        if let Ok(num) = word.parse() {
+            return Ok(Expr::Num(num));
+        }
+
+        Ok(Expr::Stat(match Keyword::from_word(&word.to_lowercase()) {
+            Some(Keyword::Attack) => Stat::Attack,
+            Some(Keyword::Health) => Stat::Health,
+            Some(Keyword::SigilCount) => Stat::SigilCount,
+            Some(Keyword::Costs) => {
+                self.expect_token(&Token::Dot, "`.`")?;
+                match self.next() {
+                    Token::Word(w) => match w.to_lowercase().as_str() {
+                        "blood" | "b" => Stat::Blood,
+                        "bone" | "o" => Stat::Bone,
+                        "energy" | "e" => Stat::Energy,
+                        "mox" | "m" => Stat::Mox,
+                        _ => return Err(self.error(QueryErrorKind::InvalidValue("cost type", w))),
+                    },
+                    tk => return Err(self.error(QueryErrorKind::Expected("a cost type", tk))),
+                }
+            }
+            _ => return Err(self.error(QueryErrorKind::Expected("a number", Token::Word(word)))),
+        }))
+    }
+
+    /// Run a parse function one level deeper.
+    ///
+    /// Every recursion in the parser (or, and, not and keyword included) goes through a group or a
+    /// negative factor so counting those is enough to bound the whole thing.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, QueryError>,
+    ) -> Result<T, QueryError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(QueryErrorKind::TooDeep));
+        }
+
+        self.depth += 1;
+        let res = parse(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn not_eof(&self) -> bool {
+        !matches!(self.curr(), Token::Eof)
+    }
+
+    fn curr(&self) -> &Token {
+        self.tokens.last().map_or(&Token::Eof, |(tk, _)| tk)
+    }
+
+    fn curr_span(&self) -> Span {
+        self.tokens.last().map_or(self.last, |&(_, span)| span)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.iter().rev().nth(1).map(|(tk, _)| tk)
+    }
+
+    /// Check if the `-` at `n` tokens ahead start a new keyword instead of being a subtraction.
+    ///
+    /// That is when the `-` come after a space and is stuck to a group or a keyword like
+    /// `-s:airborne`, `-tp~beast` or `-1<h`. A `-` surrounded by spaces is always a subtraction.
+    fn starts_term(&self, n: usize) -> bool {
+        let mut tokens = self.tokens.iter().rev().skip(n);
+        let Some((Token::Minus, span)) = tokens.next() else {
+            return false;
+        };
+
+        let prev_end = match n {
+            0 => self.last.end,
+            n => self
+                .tokens
+                .iter()
+                .rev()
+                .nth(n - 1)
+                .map_or(0, |(_, s)| s.end),
+        };
+        let next_start = tokens.clone().next().map_or(span.end, |(_, s)| s.start);
+        if prev_end == span.start || span.end != next_start {
+            return false;
+        }
+
+        matches!(
+            (tokens.next(), tokens.next()),
+            (Some((Token::OpenParen, _)), _)
+                | (
+                    Some((Token::Word(_), _)),
+                    Some((
+                        Token::Colon
+                            | Token::Tilde
+                            | Token::Equal
+                            | Token::Greater
+                            | Token::GreaterEq
+                            | Token::Less
+                            | Token::LessEq,
+                        _
+                    ))
+                )
+        )
+    }
+
+    fn next(&mut self) -> Token {
+        match self.tokens.pop() {
+            Some((tk, span)) => {
+                self.last = span;
+                tk
+            }
+            None => Token::Eof,
+        }
+    }
+
+    fn expect_token(&mut self, what: &Token, desc: &'static str) -> Result<(), QueryError> {
+        let next = self.next();
+        if &next == what {
+            Ok(())
+        } else {
+            Err(self.error(QueryErrorKind::Expected(desc, next)))
+        }
+    }
+
+    /// Create an error pointing at the last token taken.
+    fn error(&self, kind: QueryErrorKind) -> QueryError {
+        QueryError::new(kind, self.last, self.query).after(
+            self.query
+                .get(self.start..self.last.start)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(feature = "regex")]
+fn regex_keyword<E, C, F>(
+    keyword: Keyword,
+    regex: String,
+) -> Result<Filters<E, C, F>, QueryErrorKind>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    match keyword {
+        Keyword::Name => Filters::name_regex(&regex),
+        Keyword::Desc => Filters::description_regex(&regex),
+        Keyword::Tribe => Filters::tribe_regex(&regex),
+        _ => return Err(QueryErrorKind::Expected("a value", Token::Regex(regex))),
+    }
+    .map_err(QueryErrorKind::Regex)
+}
+
+#[cfg(not(feature = "regex"))]
+fn regex_keyword<E, C, F>(_: Keyword, regex: String) -> Result<Filters<E, C, F>, QueryErrorKind>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    Err(QueryErrorKind::Expected("a value", Token::Regex(regex)))
+}
+
+/// Parse a cost value like `2b` or `1b3o`. The count default to 1 when omitted.
+fn parse_costs<C: Default>(value: String) -> Result<Costs<C>, QueryErrorKind> {
+    let mut costs = Costs::default();
+    let mut count = String::new();
+
+    for c in value.to_lowercase().chars() {
+        if c.is_ascii_digit() || (c == '-' && count.is_empty()) {
+            count.push(c);
+            continue;
+        }
+
+        let n = if count.is_empty() {
+            1
+        } else {
+            count
+                .parse()
+                .map_err(|_| QueryErrorKind::InvalidValue("cost", value.clone()))?
+        };
+        count.clear();
+
+        match c {
+            'b' => costs.blood = n,
+            'o' => costs.bone = n,
+            'e' => costs.energy = n,
+            'r' => costs.mox |= Mox::O,
+            'g' => costs.mox |= Mox::G,
+            'u' => costs.mox |= Mox::B,
+            'y' => costs.mox |= Mox::Y,
+            _ => return Err(QueryErrorKind::InvalidValue("cost", value)),
+        }
+    }
+
+    if !count.is_empty() {
+        return Err(QueryErrorKind::InvalidValue("cost", value));
+    }
+
+    Ok(costs)
+}
+
+/// Parse a trait value, the well known traits are turn into flags and the rest are kept as string.
+///
+/// Multiple traits can be separated with `,` but since `,` isn't part of a bare word the value
+/// have to be quoted, like `tr:"conductive,hard"`.
+fn parse_traits(value: &str) -> Traits {
+    let mut traits = Traits::with_flags(TraitsFlag::empty());
+    let mut strings = vec![];
+
+    for t in value.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        match t.to_lowercase().as_str() {
+            "conductive" => traits.flags |= TraitsFlag::CONDUCTIVE,
+            "ban" | "banned" => traits.flags |= TraitsFlag::BAN,
+            "terrain" => traits.flags |= TraitsFlag::TERRAIN,
+            "hard" => traits.flags |= TraitsFlag::HARD,
+            _ => strings.push(t.to_owned()),
+        }
+    }
+
+    if !strings.is_empty() {
+        traits.strings = Some(strings);
+    }
+
+    traits
+}
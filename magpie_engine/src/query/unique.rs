@@ -0,0 +1,226 @@
+//! Implementation for de-duplicating the same card across sets.
+//!
+//! Many sets share the same cards, like most IMF sets having a Stoat. The [`Unique`] mode decide
+//! which of these duplicate are kept in a query and [`group_by_name`] group them together so you
+//! can show where each version appear.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Card, SetCode};
+
+/// Which card to keep when the same card appear in multiple sets, use with [`Filters::Unique`].
+///
+/// Only the first card found is kept so the order of the sets being query matter.
+///
+/// [`Filters::Unique`]: super::Filters::Unique
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Unique {
+    /// Keep 1 card for each name.
+    Names,
+    /// Keep 1 card for each identical printing, card with the same name, cost, stats and sigils.
+    Prints,
+    /// Keep every card, this is the default.
+    #[default]
+    All,
+}
+
+impl Display for Unique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Unique::Names => "names",
+                Unique::Prints => "prints",
+                Unique::All => "all",
+            }
+        )
+    }
+}
+
+/// Normalize a card name so the same card in different sets have the same name.
+///
+/// The name is lowercase and anything that is not a letter or a number is treat as space.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::query::normalize_name;
+///
+/// assert_eq!(normalize_name("Mr. Egg"), "mr egg");
+/// assert_eq!(normalize_name("  mr  EGG "), "mr egg");
+/// assert_eq!(normalize_name("Half-Beast"), "half beast");
+/// ```
+#[must_use]
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Check if 2 cards are the same printing, they have the same name, cost, stats and sigils.
+#[must_use]
+pub fn same_print<E, C>(a: &Card<E, C>, b: &Card<E, C>) -> bool
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    normalize_name(&a.name) == normalize_name(&b.name)
+        && a.costs == b.costs
+        && a.attack == b.attack
+        && a.health == b.health
+        && a.sigils == b.sigils
+}
+
+/// A version of a card and every set it appear in, see [`group_by_name`].
+#[derive(Debug)]
+pub struct CardVersion<'a, E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    /// The first card found with this version.
+    pub card: &'a Card<E, C>,
+    /// Every set this version appear in, in the order they were found.
+    pub sets: Vec<SetCode>,
+}
+
+/// Cards from different sets with the same name, see [`group_by_name`].
+#[derive(Debug)]
+pub struct CardGroup<'a, E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    /// The normalized name of the cards, see [`normalize_name`].
+    pub name: String,
+    /// The different printings of the card.
+    pub versions: Vec<CardVersion<'a, E, C>>,
+}
+
+impl<E, C> CardGroup<'_, E, C>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    /// Every set the card appear in.
+    pub fn sets(&self) -> impl Iterator<Item = SetCode> + '_ {
+        self.versions.iter().flat_map(|v| v.sets.iter().copied())
+    }
+}
+
+/// Group cards by their normalized name and then by identical printings.
+///
+/// Groups are in the order their first card was found.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+/// use magpie_engine::query::group_by_name;
+///
+/// # fn card(set: &str, name: &str, health: isize) -> Card<(), ()> {
+/// #     Card {
+/// #         set: SetCode::new(set).unwrap(),
+/// #         name: name.to_owned(),
+/// #         description: String::new(),
+/// #         portrait: String::new(),
+/// #         rarity: Rarity::COMMON,
+/// #         temple: Temple::BEAST,
+/// #         tribes: None,
+/// #         attack: Attack::Num(1),
+/// #         health,
+/// #         sigils: vec![],
+/// #         traits: None,
+/// #         related: vec![],
+/// #         costs: None,
+/// #         extra: (),
+/// #     }
+/// # }
+/// let cards = [card("std", "Stoat", 3), card("ete", "stoat", 3), card("egg", "Stoat", 2)];
+///
+/// let groups = group_by_name(&cards);
+/// assert_eq!(groups.len(), 1);
+///
+/// // std and ete have the same stoat while egg buff it
+/// let stoat = &groups[0];
+/// assert_eq!(stoat.versions.len(), 2);
+/// assert_eq!(stoat.versions[0].sets, [SetCode::new("std").unwrap(), SetCode::new("ete").unwrap()]);
+/// ```
+pub fn group_by_name<'a, E, C>(
+    cards: impl IntoIterator<Item = &'a Card<E, C>>,
+) -> Vec<CardGroup<'a, E, C>>
+where
+    E: Clone + 'a,
+    C: Clone + PartialEq + 'a,
+{
+    let mut groups: Vec<CardGroup<'a, E, C>> = vec![];
+    let mut lookup: HashMap<String, usize> = HashMap::new();
+
+    for card in cards {
+        let i = *lookup
+            .entry(normalize_name(&card.name))
+            .or_insert_with_key(|name| {
+                groups.push(CardGroup {
+                    name: name.clone(),
+                    versions: vec![],
+                });
+                groups.len() - 1
+            });
+        let group = &mut groups[i];
+
+        match group.versions.iter_mut().find(|v| same_print(v.card, card)) {
+            Some(v) => v.sets.push(card.set),
+            None => group.versions.push(CardVersion {
+                card,
+                sets: vec![card.set],
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Keep track of the card already seen to remove duplicate.
+pub(super) struct Seen<T> {
+    mode: Unique,
+    names: HashMap<String, Vec<T>>,
+}
+
+impl<T, E, C> Seen<T>
+where
+    T: Deref<Target = Card<E, C>>,
+    E: Clone,
+    C: Clone + PartialEq,
+{
+    pub(super) fn new(mode: Unique) -> Self {
+        Seen {
+            mode,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Return true if the card is not a duplicate of a card already seen.
+    pub(super) fn insert(&mut self, card: T) -> bool {
+        if self.mode == Unique::All {
+            return true;
+        }
+
+        let seen = self.names.entry(normalize_name(&card.name)).or_default();
+        let new = match self.mode {
+            Unique::Names => seen.is_empty(),
+            Unique::Prints => !seen.iter().any(|c| same_print(c, &card)),
+            Unique::All => unreachable!(),
+        };
+
+        if new {
+            seen.push(card);
+        }
+
+        new
+    }
+}
@@ -0,0 +1,323 @@
+//! Convert filters back into the query syntax.
+//!
+//! The output is canonical, every keyword use its short alias and value are only quoted when
+//! needed so parsing it again give back the same query string.
+
+use std::fmt::Write;
+
+use crate::{Mox, Rarity, SpAtk, Temple, Traits, TraitsFlag};
+
+use super::{Expr, Filters, QueryOrder, Shorthand, Stat, ToFilter};
+
+/// Convert a list of filters into a query string that [`parse`](super::parse) accept.
+///
+/// Return [`None`] if any of the filter can't be express in the query syntax, like
+/// [`Filters::StrAtk`] or an extra filter that don't implement
+/// [`ToFilter::to_query_string`].
+///
+/// # Examples
+/// ```
+/// use magpie_engine::prelude::*;
+///
+/// let filters: Vec<Filters<(), (), ()>> =
+///     query::parse("attack>=3 !sigil:airborne (name:\"mr. egg\" OR tb:bird) c:2b").unwrap();
+///
+/// let text = query::to_query_string(&filters).unwrap();
+/// assert_eq!(text, "a>=3 -s:airborne (n:\"mr. egg\" or tb:bird) c:2b");
+///
+/// // parsing the text again give back the same filters
+/// let parsed: Vec<Filters<(), (), ()>> = query::parse(&text).unwrap();
+/// assert_eq!(format!("{parsed:?}"), format!("{filters:?}"));
+///
+/// // negating a negation or a negative number still give something the parser accept
+/// let filters: Vec<Filters<(), (), ()>> = vec![
+///     Filters::Not(Box::new(Filters::Not(Box::new(Filters::Sigil("airborne".to_owned()))))),
+///     Filters::Not(Box::new(Filters::Expr(
+///         Expr::Num(-1),
+///         QueryOrder::Less,
+///         Expr::Stat(Stat::Health),
+///     ))),
+/// ];
+/// let text = query::to_query_string(&filters).unwrap();
+/// let parsed: Vec<Filters<(), (), ()>> = query::parse(&text).unwrap();
+/// assert_eq!(format!("{parsed:?}"), format!("{filters:?}"));
+///
+/// // filters can also be save with serde
+/// let json = serde_json::to_string(&filters).unwrap();
+/// let filters: Vec<Filters<(), (), ()>> = serde_json::from_str(&json).unwrap();
+///
+/// assert_eq!(query::to_query_string(&filters).unwrap(), text);
+/// ```
+pub fn to_query_string<E, C, F>(filters: &[Filters<E, C, F>]) -> Option<String>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    filters
+        .iter()
+        .map(filter_to_query)
+        .collect::<Option<Vec<_>>>()
+        .map(|f| f.join(" "))
+}
+
+/// Quote a keyword value if it is not a plain word.
+///
+/// # Examples
+/// ```
+/// use magpie_engine::query::quote_value;
+///
+/// assert_eq!(quote_value("stoat"), "stoat");
+/// assert_eq!(quote_value("mr. \"egg\""), r#""mr. \"egg\"""#);
+/// ```
+#[must_use]
+pub fn quote_value(value: &str) -> String {
+    let plain = value.chars().next().is_some_and(is_word)
+        && value.chars().all(|c| is_word(c) || c == '-' || c == '\'');
+
+    if plain {
+        value.to_owned()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub(super) fn filter_to_query<E, C, F>(filter: &Filters<E, C, F>) -> Option<String>
+where
+    E: Clone,
+    C: Clone + PartialEq,
+    F: ToFilter<E, C>,
+{
+    let kw = |kw: &str, value: &str| Some(format!("{kw}:{}", quote_value(value)));
+
+    match filter {
+        Filters::Name(name) => kw("n", name),
+        #[cfg(feature = "regex")]
+        Filters::NameRegex(r) => regex_to_query("n", r.as_str()),
+        #[cfg(feature = "regex")]
+        Filters::DescriptionRegex(r) => regex_to_query("d", r.as_str()),
+        #[cfg(feature = "regex")]
+        Filters::TribeRegex(r) => regex_to_query("tb", r.as_str()),
+        Filters::Set(code) => kw("e", code.code()),
+        Filters::Description(desc) => kw("d", desc),
+
+        Filters::Rarity(rarity) => kw(
+            "r",
+            match rarity {
+                Rarity::SIDE => "side",
+                Rarity::COMMON => "common",
+                Rarity::UNCOMMON => "uncommon",
+                Rarity::RARE => "rare",
+                Rarity::UNIQUE => "unique",
+            },
+        ),
+        Filters::Temple(temple) => kw(
+            "tp",
+            [
+                (Temple::BEAST, "beast"),
+                (Temple::UNDEAD, "undead"),
+                (Temple::TECH, "tech"),
+                (Temple::MAGICK, "magick"),
+                (Temple::FOOL, "fool"),
+                (Temple::ARTISTRY, "artistry"),
+            ]
+            .into_iter()
+            .find(|(t, _)| t == temple)?
+            .1,
+        ),
+        Filters::Tribe(tribe) => kw("tb", tribe.as_ref()?),
+
+        Filters::Attack(o, a) => Some(format!("a{}{a}", order_to_query(o))),
+        Filters::Health(o, h) => Some(format!("h{}{h}", order_to_query(o))),
+        Filters::Expr(a, o, b) => Some(format!(
+            "{} {} {}",
+            expr_to_query(a, 0)?,
+            order_to_query(o),
+            expr_to_query(b, 0)?
+        )),
+
+        Filters::Sigil(sigil) => kw("s", sigil),
+        Filters::SigilDescription(text) => kw("st", text),
+        Filters::SigilCount(o, c) => Some(format!("sc{}{c}", order_to_query(o))),
+
+        Filters::SpAtk(sp) => kw(
+            "sp",
+            match sp {
+                SpAtk::MOX => "mox",
+                SpAtk::GREEN_MOX => "green",
+                SpAtk::MIRROR => "mirror",
+                SpAtk::ANT => "ant",
+                SpAtk::BONE => "bone",
+                SpAtk::BELL => "bell",
+                SpAtk::CARD => "card",
+            },
+        ),
+        Filters::StrAtk(_) => None,
+
+        Filters::Costs(costs) => {
+            let costs = costs.as_ref()?;
+            if costs.mox_count.is_some() {
+                return None;
+            }
+
+            let mut out = String::new();
+            for (count, c) in [(costs.blood, 'b'), (costs.bone, 'o'), (costs.energy, 'e')] {
+                if count != 0 {
+                    let _ = write!(out, "{count}{c}");
+                }
+            }
+
+            let mut mox = costs.mox;
+            for (m, c) in [(Mox::O, 'r'), (Mox::G, 'g'), (Mox::B, 'u'), (Mox::Y, 'y')] {
+                if mox.contains(m) {
+                    out.push(c);
+                    mox.remove(m);
+                }
+            }
+
+            // other mox color can't be express
+            if !mox.is_empty() {
+                return None;
+            }
+
+            kw("c", if out.is_empty() { "0b" } else { &out })
+        }
+        Filters::Traits(traits) => kw("tr", &traits_to_query(traits.as_ref()?)?),
+
+        Filters::Related(None) => kw("rel", "any"),
+        Filters::Related(Some(r)) if r.eq_ignore_ascii_case("any") => None,
+        Filters::Related(Some(r)) => kw("rel", r),
+        Filters::RelatedOf(f) => match &**f {
+            Filters::Name(name) => kw("rof", name),
+            // the group already have its own parentheses
+            f @ (Filters::And(..) | Filters::Or(..)) => {
+                Some(format!("rof:{}", filter_to_query(f)?))
+            }
+            f => Some(format!("rof:({})", filter_to_query(f)?)),
+        },
+
+        Filters::Is(s) => kw(
+            "is",
+            match s {
+                Shorthand::Free => "free",
+                Shorthand::Token => "token",
+                Shorthand::Split => "split",
+                Shorthand::Evolves => "evolves",
+                Shorthand::MultiTemple => "multitemple",
+            },
+        ),
+
+        Filters::Unique(u) => kw("unique", &u.to_string()),
+
+        Filters::And(a, b) => Some(format!(
+            "({} and {})",
+            filter_to_query(a)?,
+            filter_to_query(b)?
+        )),
+        Filters::Or(a, b) => Some(format!(
+            "({} or {})",
+            filter_to_query(a)?,
+            filter_to_query(b)?
+        )),
+        Filters::Not(f) => {
+            let f = filter_to_query(f)?;
+            if f.starts_with(['-', '!']) {
+                // `--s:x` doesn't parse so nested negation and negative number go in a group
+                Some(format!("-({f})"))
+            } else if f.starts_with(|c: char| c.is_ascii_digit()) {
+                // `-1<a` would be a negative number so use `!` instead
+                Some(format!("!{f}"))
+            } else {
+                Some(format!("-{f}"))
+            }
+        }
+
+        Filters::Extra(f) => f.to_query_string(),
+        Filters::McGuffin(..) | Filters::Cake(..) => unreachable!(),
+    }
+}
+
+#[cfg(feature = "regex")]
+fn regex_to_query(kw: &str, pattern: &str) -> Option<String> {
+    // a trailing `\` would escape the closing `/`
+    if pattern.ends_with('\\') {
+        return None;
+    }
+    Some(format!("{kw}:/{}/", pattern.replace('/', "\\/")))
+}
+
+fn order_to_query(order: &QueryOrder) -> &'static str {
+    match order {
+        QueryOrder::Greater => ">",
+        QueryOrder::GreaterEqual => ">=",
+        QueryOrder::Equal => "=",
+        QueryOrder::LessEqual => "<=",
+        QueryOrder::Less => "<",
+    }
+}
+
+/// Convert an expression to the query syntax. The query syntax have no parentheses for
+/// expression so expression that need them return [`None`].
+fn expr_to_query(expr: &Expr, prec: u8) -> Option<String> {
+    let (op, this, a, b) = match expr {
+        Expr::Num(n) => return Some(n.to_string()),
+        Expr::Stat(s) => {
+            return Some(
+                match s {
+                    Stat::Attack => "a",
+                    Stat::Health => "h",
+                    Stat::Blood => "cost.b",
+                    Stat::Bone => "cost.o",
+                    Stat::Energy => "cost.e",
+                    Stat::Mox => "cost.m",
+                    Stat::SigilCount => "sc",
+                }
+                .to_owned(),
+            )
+        }
+        Expr::Add(a, b) => ("+", 1, a, b),
+        Expr::Sub(a, b) => ("-", 1, a, b),
+        Expr::Mul(a, b) => ("*", 2, a, b),
+        Expr::Div(a, b) => ("/", 2, a, b),
+    };
+
+    if this < prec {
+        return None;
+    }
+
+    // the right side bind tighter since operator are left associative
+    Some(format!(
+        "{} {op} {}",
+        expr_to_query(a, this)?,
+        expr_to_query(b, this + 1)?
+    ))
+}
+
+fn traits_to_query(traits: &Traits) -> Option<String> {
+    let mut out = vec![];
+
+    for (f, name) in [
+        (TraitsFlag::CONDUCTIVE, "conductive"),
+        (TraitsFlag::BAN, "banned"),
+        (TraitsFlag::TERRAIN, "terrain"),
+        (TraitsFlag::HARD, "hard"),
+    ] {
+        if traits.flags.contains(f) {
+            out.push(name);
+        }
+    }
+
+    for s in traits.strings.iter().flatten() {
+        // trait are split on `,` when parsing
+        if s.contains(',') {
+            return None;
+        }
+        out.push(s);
+    }
+
+    Some(out.join(","))
+}
@@ -0,0 +1,129 @@
+//! Card aliases for community shorthand that fuzzy search can't get right.
+
+use std::{collections::HashMap, fs, hash::BuildHasher};
+
+use magpie_engine::query::normalize_name;
+
+use crate::{
+    card_candidates, clear_miss, did_you_mean, done, error, fuzzy_top, Card, Color, Set, ALIASES,
+    SETS,
+};
+
+/// Type alias for the local alias store, map set code to alias to card name.
+pub type AliasStore = HashMap<String, HashMap<String, String>>;
+
+/// Location of the local alias file.
+pub const ALIAS_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "\\aliases.json");
+
+/// Built-in aliases, they are added to every set that have the card.
+pub const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("mole", "Mole Man"),
+    ("ouro", "Ouroboros"),
+    ("kraken", "Great Kraken"),
+];
+
+/// Load the aliases from the local alias file.
+pub(crate) fn load_aliases() -> AliasStore {
+    let Ok(content) = fs::read_to_string(ALIAS_FILE_PATH) else {
+        return AliasStore::new();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        error!("Cannot parse alias file: {err}");
+        AliasStore::new()
+    })
+}
+
+/// Save the aliases to the alias file.
+pub fn save_aliases() {
+    let content = serde_json::to_string_pretty(&*ALIASES.lock().unwrap()).unwrap();
+    fs::write(ALIAS_FILE_PATH, content).expect("Cannot write alias file");
+    done!("Aliases save successfully to {}", ALIAS_FILE_PATH.green());
+}
+
+/// Add the default and local aliases to the sets.
+pub fn apply_aliases<S: BuildHasher>(sets: &mut HashMap<&'static str, Set, S>) {
+    let store = ALIASES.lock().unwrap();
+
+    for (code, set) in sets.iter_mut() {
+        for (alias, name) in DEFAULT_ALIASES {
+            if let Some(card) = find_card(set, name) {
+                let name = card.name.clone();
+                set.add_alias(alias, name);
+            }
+        }
+
+        for (alias, name) in store.get(*code).into_iter().flatten() {
+            set.add_alias(alias, name.clone());
+        }
+    }
+}
+
+/// Add an alias to a set and save it to the local alias file.
+///
+/// Return the name of the card the alias now refer to or a message explaining why the alias
+/// can't be added.
+pub fn register_alias(set_code: &str, alias: &str, card: &str) -> Result<String, String> {
+    if normalize_name(alias).is_empty() {
+        return Err("The alias can't be empty.".to_owned());
+    }
+
+    let (set_code, name) = {
+        let mut sets = SETS.lock().unwrap();
+        let Some(set_code) = set_key(&sets, set_code) else {
+            return Err(format!("There are no set with code `{set_code}`."));
+        };
+        let set = sets.get_mut(set_code).unwrap();
+
+        let Some(card) = find_card(set, card) else {
+            let close: Vec<_> = fuzzy_top(card, card_candidates(set), 0.25, 3)
+                .into_iter()
+                .map(|r| r.data.name.clone())
+                .collect();
+
+            return Err(format!(
+                "There are no card named \"{card}\" in {}. {}",
+                set.name,
+                did_you_mean(&close).unwrap_or_default()
+            ));
+        };
+
+        let name = card.name.clone();
+        set.add_alias(alias, name.clone());
+        (set_code, name)
+    };
+
+    ALIASES
+        .lock()
+        .unwrap()
+        .entry(set_code.to_owned())
+        .or_default()
+        .insert(normalize_name(alias), name.clone());
+    save_aliases();
+
+    // the alias fix the miss so no need to keep it around
+    clear_miss(set_code, alias);
+
+    Ok(name)
+}
+
+/// Find the key of a set from a set code ignoring case.
+///
+/// An exact match win since some set code only differ by case like `aug` and `Aug`.
+fn set_key<S: BuildHasher>(
+    sets: &HashMap<&'static str, Set, S>,
+    code: &str,
+) -> Option<&'static str> {
+    sets.get_key_value(code).map(|(k, _)| *k).or_else(|| {
+        sets.keys()
+            .filter(|k| k.eq_ignore_ascii_case(code))
+            .min()
+            .copied()
+    })
+}
+
+/// Find a card in a set by its normalized name.
+fn find_card<'a>(set: &'a Set, name: &str) -> Option<&'a Card> {
+    let name = normalize_name(name);
+    set.cards.iter().find(|c| normalize_name(&c.name) == name)
+}
@@ -7,6 +7,9 @@ use rand::thread_rng;
 mod query_help;
 pub use query_help::*;
 
+mod alias;
+pub use alias::*;
+
 /// Test command
 #[poise::command(slash_command)]
 pub async fn test(ctx: CmdCtx<'_>) -> Res {
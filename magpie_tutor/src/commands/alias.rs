@@ -0,0 +1,101 @@
+use std::fmt::Write;
+
+use poise::serenity_prelude::{
+    CreateActionRow, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+};
+
+use crate::{register_alias, top_misses, CmdCtx, MessageAdapter, Miss, Res};
+
+/// Add an alias for a card so searching for the alias find the card.
+#[poise::command(
+    slash_command,
+    default_member_permissions = "MANAGE_GUILD",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn add_alias(
+    ctx: CmdCtx<'_>,
+    #[description = "The set code of the set the card is in, like `std` or `aug`"] set: String,
+    #[description = "The alias to add, case and punctuation doesn't matter"] alias: String,
+    #[description = "The full name of the card"] card: String,
+) -> Res {
+    let msg = match register_alias(&set, &alias, &card) {
+        Ok(name) => format!("Added alias \"{alias}\" for {name} in `{set}`."),
+        Err(err) => err,
+    };
+
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Show the most common search that found nothing or only a poor match.
+#[poise::command(
+    slash_command,
+    default_member_permissions = "MANAGE_GUILD",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn misses(ctx: CmdCtx<'_>) -> Res {
+    ctx.send(misses_message().into()).await?;
+    Ok(())
+}
+
+/// Generate the message listing the top misses with a select menu to map them to their best
+/// guess.
+pub fn misses_message() -> MessageAdapter {
+    let misses = top_misses(MISS_LIST_LEN);
+
+    if misses.is_empty() {
+        return MessageAdapter::new()
+            .content("No missed search recorded.".to_owned())
+            .ephemeral(true);
+    }
+
+    let mut content = String::from("Top missed searches:\n");
+    let mut options = vec![];
+
+    for (i, Miss { set, term, data }) in misses.iter().enumerate() {
+        let _ = writeln!(
+            content,
+            "{}. \"{term}\" in `{set}` ({} times), best guess: {}",
+            i + 1,
+            data.count,
+            data.guess.as_deref().unwrap_or("none")
+        );
+
+        // select option value can only be 100 characters long
+        if let Some(guess) = &data.guess {
+            let value = format!("{set}:{term}");
+            if value.len() <= 100 {
+                options.push(
+                    CreateSelectMenuOption::new(truncate(&format!("{term} → {guess}")), value)
+                        .description(format!("Add \"{term}\" as an alias for {guess} in {set}")),
+                );
+            }
+        }
+    }
+
+    content.push_str("\nPick a miss below to add it as an alias for its best guess, use `/add_alias` for anything else.");
+
+    let components = if options.is_empty() {
+        vec![]
+    } else {
+        vec![CreateActionRow::SelectMenu(
+            CreateSelectMenu::new("map_miss", CreateSelectMenuKind::String { options })
+                .placeholder("Map a miss to its best guess"),
+        )]
+    };
+
+    MessageAdapter::new()
+        .content(content)
+        .components(components)
+        .ephemeral(true)
+}
+
+/// How many misses to show, a select menu can only have 25 options.
+const MISS_LIST_LEN: usize = 15;
+
+/// Select menu label can only be 100 characters long.
+fn truncate(text: &str) -> String {
+    text.chars().take(100).collect()
+}
@@ -3,7 +3,8 @@ use crate::{CmdCtx, Res};
 macro_rules! query_help {
     ($($key:ident [$($alias:ident),*]: $desc:literal);*) => {
         concat!(
-            "Possible query keyword for searching:\nHow to read: [keyword name] [keyword alias]: [keyword description]\n\n",
+            "Possible query keyword for searching:\nHow to read: [keyword name] [keyword alias]: [keyword description]\n",
+            "Word without a keyword search by name. Negate a keyword with `!` or `-` and combine them with `and`, `or` and parentheses.\n\n",
             $(concat!("- ", stringify!($key), " [", $(stringify!($alias))*, "]: ", $desc, "\n")),*
         )
     };
@@ -13,18 +14,26 @@ macro_rules! query_help {
 #[poise::command(slash_command)]
 pub async fn query_help(ctx: CmdCtx<'_>) -> Res {
     ctx.say(query_help!(
-        name [n]: "Filter for name.";
-        description [d]: "Filter for description.";
+        name [n]: "Filter for name. Use `~` instead of `:` to match similar name or surround the value in `/` to match a regex like `n:/^(Mr|Ms)\\./`. Surround the value in `\"` to include space or punctuation, use `\\\"` for a quote inside.";
+        description [d]: "Filter for description. Surround the value in `/` to match a regex.";
+        set [e]: "Filter for the set the card belong to. Use the set code like `ete` or `aug`. Query with this keyword search all set so you don't need to select them.";
         rarity [r]: "Filter for rarity. Possible values: `side`, `common`, `rare`, `unique` as well as their shorthand.";
         temple [tp]: "Filter for temple. Possible values: `beast`, `undead`, `technology`, `fool`, `artistry` as well as their shorthand.";
-        tribe [tb]: "Filter for tribe.";
-        attack [a]: "Filter for attack.";
-        health [h]: "Filter for health.";
-        sigil [s]: "Filter for sigil.";
+        tribe [tb]: "Filter for tribe. Surround the value in `/` to match a regex.";
+        attack [a]: "Filter for attack. Can be compare against an expression like `a>h`, `a+h>=6` or `a>cost.blood`.";
+        health [h]: "Filter for health. Can be compare against an expression like `h>=2*a`.";
+        sigil [s]: "Filter for sigil. Match partial sigil name, use `~` instead of `:` to match similar sigil name.";
+        sigils [sc]: "Filter for the number of sigils.";
+        sigiltext [st]: "Filter for sigil description. Match card with any sigil that mention the value.";
         spatk [sp]: "Filter for special attack. Possible values: `mox`, `green`, `mirror`, `ant`, `bone`, `bell`, `card`.";
         cost [c]: "Filter for cost. Must follow the format of `[amount][type]`. Example `1b` would search for 1 blood, `3b6e2o` would search for 3 blood, 6 energy and 2 bones.";
         costtype [ct]: "Filter for cost type. Possible values: `b`, `o`, `e`, `m` and any combination of them.";
-        trait [tr]: "Filter for trait."
+        artist [art]: "Filter for the card artist. Match partial artist name, only Augmented card have artist credit.";
+        trait [tr]: "Filter for trait. Possible values: `conductive`, `ban`, `terrain`, `hard` or any named trait. Use multiple `trait` keywords or separate the traits with `,` inside quotes like `tr:\"conductive,hard\"` to match card that have all of them.";
+        related [rel]: "Filter for related card like token or evolution. Match partial related card name or use `any` to match card with any related card.";
+        relatedof [rof]: "Filter for card that are the related card of another card. Take a name like `rof:bird` or a group of keyword like `rof:(s:fledgling)`.";
+        is [is]: "Shorthand filter. Possible values: `free`, `vanilla`, `token`, `rare`, `conductive`, `terrain`, `banned`, `hard`, `split` (cost more than 1 resource type), `evolves` and `multitemple`.";
+        unique [unique]: "Remove the same card from other sets when querying multiple sets. Possible values: `names` (1 card per name), `prints` (1 card per identical cost, stats and sigils) and `all` (the default)."
     ))
     .await?;
 
@@ -4,6 +4,7 @@ use std::fmt::Display;
 
 use bitflags::bitflags;
 use magpie_engine::prelude::*;
+use magpie_engine::query::quote_value;
 
 use crate::lev;
 
@@ -48,8 +49,12 @@ impl Display for CostType {
 pub enum FilterExt {
     /// Fuzzy match the card name
     Fuzzy(String),
-    /// Fuzzy match the card name
+    /// Match the card cost type
     CostType(CostType),
+    /// Fuzzy match the card sigils name
+    FuzzySigil(String),
+    /// Match a field in [`MagpieExt`] like the artist
+    Field(FieldFilter),
 }
 
 impl ToFilter<MagpieExt, MagpieCosts> for FilterExt {
@@ -58,6 +63,16 @@ impl ToFilter<MagpieExt, MagpieCosts> for FilterExt {
             FilterExt::Fuzzy(str) => {
                 Box::new(move |c| lev(&c.name, &str, 0.5) != 0. || c.name.contains(&str))
             }
+            FilterExt::FuzzySigil(str) => {
+                let str = str.to_lowercase();
+                Box::new(move |c| {
+                    c.sigils.iter().any(|s| {
+                        let s = s.to_lowercase();
+                        lev(&s, &str, 0.5) != 0. || s.contains(&str)
+                    })
+                })
+            }
+            FilterExt::Field(f) => f.to_fn(),
             FilterExt::CostType(t) => Box::new(move |c| {
                 if let Some(c) = &c.costs {
                     !(t.contains(CostType::BLOOD) && c.blood == 0
@@ -70,6 +85,33 @@ impl ToFilter<MagpieExt, MagpieCosts> for FilterExt {
             }),
         }
     }
+
+    fn to_query_string(&self) -> Option<String> {
+        match self {
+            FilterExt::Fuzzy(n) => Some(format!("n~{}", quote_value(n))),
+            FilterExt::FuzzySigil(s) => Some(format!("s~{}", quote_value(s))),
+            FilterExt::Field(f) if f.field == "artist" => {
+                Some(format!("art:{}", quote_value(&f.value)))
+            }
+            FilterExt::Field(_) => None,
+            FilterExt::CostType(t) => {
+                let flags = [
+                    (CostType::BLOOD, 'b'),
+                    (CostType::BONE, 'o'),
+                    (CostType::ENERGY, 'e'),
+                    (CostType::MOX, 'm'),
+                ];
+
+                let t: String = flags
+                    .into_iter()
+                    .filter(|(f, _)| t.contains(f.clone()))
+                    .map(|(_, c)| c)
+                    .collect();
+
+                Some(format!("ct:{}", quote_value(&t)))
+            }
+        }
+    }
 }
 
 impl Display for FilterExt {
@@ -77,6 +119,8 @@ impl Display for FilterExt {
         match self {
             FilterExt::Fuzzy(n) => write!(f, "name similar to {n}"),
             FilterExt::CostType(t) => write!(f, "cost includes {t}"),
+            FilterExt::FuzzySigil(s) => write!(f, "have a sigil similar to {s}"),
+            FilterExt::Field(field) => write!(f, "{field}"),
         }
     }
 }
@@ -88,6 +132,15 @@ pub struct MagpieExt {
     pub artist: String,
 }
 
+impl ExtFields for MagpieExt {
+    fn field(&self, name: &str) -> Option<&str> {
+        match name {
+            "artist" => Some(&self.artist),
+            _ => None,
+        }
+    }
+}
+
 /// Magpie's [`Costs`] extension to unify all cost
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct MagpieCosts {
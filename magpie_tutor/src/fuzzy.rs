@@ -1,9 +1,11 @@
 //! Simple implementation for simple fuzzy sorting.
 
-use std::{
-    cmp::{max, min},
-    fmt::Debug,
-};
+use std::borrow::Cow;
+use std::cmp::{max, min};
+
+use magpie_engine::query::normalize_name;
+
+use crate::{Card, Set};
 
 /// Result for fuzzy
 #[derive(Debug)]
@@ -15,37 +17,140 @@ pub struct FuzzyRes<'a, T> {
 }
 
 /// Fuzzy match and return the best item.
-pub fn fuzzy_best<'a, T, F>(
+///
+/// The candidates are pair of a normalized name (see [`normalize_name`]) and the item, see
+/// [`fuzzy_top`].
+pub fn fuzzy_best<'a, T, S>(
     value: &str,
-    vec: Vec<&'a T>,
+    candidates: impl IntoIterator<Item = (S, &'a T)>,
     threshold: f32,
-    mut f: F,
 ) -> Option<FuzzyRes<'a, T>>
 where
-    F: FnMut(&T) -> &str,
-    T: Debug,
+    S: AsRef<str>,
+{
+    fuzzy_top(value, candidates, threshold, 1).pop()
+}
+
+/// Fuzzy match and return the best `n` items from best to worst.
+///
+/// The candidates are pair of a normalized name (see [`normalize_name`]) and the item, the name
+/// should be normalize ahead of time so we don't have to do it on every search. Only items with a
+/// score of at least `threshold` are return. Items with the same score are sort by name so the
+/// result doesn't depend on the order of the candidates.
+pub fn fuzzy_top<'a, T, S>(
+    value: &str,
+    candidates: impl IntoIterator<Item = (S, &'a T)>,
+    threshold: f32,
+    n: usize,
+) -> Vec<FuzzyRes<'a, T>>
+where
+    S: AsRef<str>,
 {
-    let mut best = None;
-
-    for v in vec {
-        let r = lev(
-            f(v).to_lowercase().as_str(),
-            value.to_lowercase().as_str(),
-            threshold,
-        );
-
-        best = match best {
-            // if the current rank is better change it
-            Some(FuzzyRes { rank, .. }) if r >= rank => Some(FuzzyRes { rank: r, data: v }),
-            // This case only trigger once and set the starting value
-            None if r > 0. => Some(FuzzyRes { rank: r, data: v }),
-
-            // Anythign else keep it as is
-            same => same,
-        };
+    let value = normalize_name(value);
+
+    let mut res: Vec<(f32, S, &T)> = candidates
+        .into_iter()
+        .filter_map(|(name, data)| {
+            let rank = score(name.as_ref(), &value);
+            (rank > 0. && rank >= threshold).then_some((rank, name, data))
+        })
+        .collect();
+
+    res.sort_by(|(r1, n1, _), (r2, n2, _)| {
+        r2.total_cmp(r1).then_with(|| n1.as_ref().cmp(n2.as_ref()))
+    });
+
+    res.into_iter()
+        .take(n)
+        .map(|(rank, _, data)| FuzzyRes { rank, data })
+        .collect()
+}
+
+/// Fuzzy match a card in a set and return the best `n` cards from best to worst.
+///
+/// The set aliases are check first so if the value is an alias, the card it refer to is the best
+/// match with a perfect score.
+pub fn fuzzy_cards<'a>(
+    set: &'a Set,
+    value: &str,
+    threshold: f32,
+    n: usize,
+) -> Vec<FuzzyRes<'a, Card>> {
+    fuzzy_top(
+        set.alias(value).unwrap_or(value),
+        card_candidates(set),
+        threshold,
+        n,
+    )
+}
+
+/// The normalized name of every card in a set to use as fuzzy candidates.
+///
+/// Use the names from the set index if there is one so they don't have to be normalize again.
+pub fn card_candidates(set: &Set) -> Vec<(Cow<'_, str>, &Card)> {
+    match set.index.as_ref().filter(|i| i.len() == set.cards.len()) {
+        Some(index) => index
+            .names()
+            .iter()
+            .map(|n| Cow::Borrowed(n.as_str()))
+            .zip(&set.cards)
+            .collect(),
+        None => set
+            .cards
+            .iter()
+            .map(|c| (Cow::Owned(normalize_name(&c.name)), c))
+            .collect(),
+    }
+}
+
+/// Format a list of close match into a "Did you mean" message, return [`None`] if there are no
+/// match.
+pub fn did_you_mean(names: &[impl AsRef<str>]) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+
+    let names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+    Some(format!("Did you mean: {}?", names.join(", ")))
+}
+
+/// Score how similar a candidate is to the search value, both should already be normalized.
+///
+/// The score is between 0 and 1 and mix the levenshtein distance with how much of the value is a
+/// prefix of the candidate and how many word of the value are in the candidate. The prefix and
+/// word can only raise the score so a close spelling is never rank lower than with levenshtein
+/// alone.
+pub fn score(candidate: &str, value: &str) -> f32 {
+    if candidate == value {
+        return 1.;
     }
 
-    best
+    let dist = lev(candidate, value, 0.);
+
+    let len = value.chars().count();
+    let prefix = if len == 0 {
+        0.
+    } else {
+        let common = candidate
+            .chars()
+            .zip(value.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        common as f32 / len as f32
+    };
+
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let tokens = if words.is_empty() {
+        0.
+    } else {
+        let found = words
+            .iter()
+            .filter(|w| candidate.split_whitespace().any(|c| c == **w))
+            .count();
+        found as f32 / words.len() as f32
+    };
+
+    dist.max(0.6 * dist + 0.2 * prefix + 0.2 * tokens)
 }
 
 /// Normalize levenshtein distance.
@@ -56,7 +161,9 @@ pub fn lev(string1: &str, string2: &str, threshold: f32) -> f32 {
         return 0.;
     }
 
-    let l1 = string1.len();
+    // count chars and not bytes since we iterate over the chars
+    let l1 = string1.chars().count();
+    let l2 = string2.chars().count();
     let mut prev_dist: Vec<usize> = (0..=l1).collect();
 
     for (row, c2) in string2.chars().enumerate() {
@@ -83,7 +190,7 @@ pub fn lev(string1: &str, string2: &str, threshold: f32) -> f32 {
         }
     }
 
-    let max = max(string1.len(), string2.len());
+    let max = max(l1, l2);
 
     // Normalize the distance
     let t = (max - prev_dist[l1]) as f32 / max as f32;
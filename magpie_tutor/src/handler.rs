@@ -1,7 +1,9 @@
 use poise::{
     serenity_prelude::{
         self as serenity, ComponentInteraction, ComponentInteractionData,
-        ComponentInteractionDataKind::Button, Context as EvtCtx, FullEvent::*,
+        ComponentInteractionDataKind::{Button, StringSelect},
+        Context as EvtCtx,
+        FullEvent::*,
         Interaction::Component,
     },
     FrameworkContext,
@@ -11,9 +13,11 @@ use crate::{done, error, search::search_message, Color, Data, Error, Res};
 
 mod button;
 mod message;
+mod select;
 
 use button::button_handler;
 use message::message_handler;
+use select::select_handler;
 
 /// The event handler or dispatcher for serenity event.
 pub async fn handler(
@@ -37,7 +41,7 @@ pub async fn handler(
         Message { new_message: msg }
             if msg.author.id != ctx.cache.current_user().id && msg.content.contains("[[") =>
         {
-            search_message(ctx, msg, msg.guild_id.unwrap()).await
+            search_message(ctx, msg, msg.guild_id).await
         }
 
         Message { new_message: msg } => message_handler(msg, ctx).await,
@@ -58,6 +62,22 @@ pub async fn handler(
                 ),
         } => button_handler(interaction, ctx, custom_id).await,
 
+        // handle select menu
+        InteractionCreate {
+            interaction:
+                Component(
+                    interaction @ ComponentInteraction {
+                        data:
+                            ComponentInteractionData {
+                                custom_id,
+                                kind: StringSelect { values },
+                                ..
+                            },
+                        ..
+                    },
+                ),
+        } => select_handler(interaction, ctx, custom_id, values).await,
+
         _ => Ok(()),
     };
 
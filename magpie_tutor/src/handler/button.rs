@@ -108,7 +108,7 @@ async fn retry(interaction: &ComponentInteraction, ctx: &Context) -> Res {
                         .await?
                         .content
                         .as_str(),
-                    interaction.guild_id.unwrap(),
+                    interaction.guild_id,
                 )
                 .into(),
             ),
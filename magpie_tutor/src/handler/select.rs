@@ -0,0 +1,75 @@
+use poise::serenity_prelude::{
+    ComponentInteraction, Context, CreateInteractionResponse::UpdateMessage,
+};
+
+use crate::commands::misses_message;
+use crate::search::process_search;
+use crate::{done, info, register_alias, Color, MessageAdapter, Res, MISSES};
+
+pub async fn select_handler(
+    interaction: &ComponentInteraction,
+    ctx: &Context,
+    custom_id: &str,
+    values: &[String],
+) -> Res {
+    match custom_id {
+        "map_miss" => map_miss(interaction, ctx, values).await,
+        id if id.starts_with("pick_card_") => pick_card(interaction, ctx, values).await,
+        _ => Ok(()),
+    }
+}
+
+/// Replace the search result with the card that was pick from the close matches.
+async fn pick_card(interaction: &ComponentInteraction, ctx: &Context, values: &[String]) -> Res {
+    // the value is the search to run for the card
+    let Some(search) = values.first() else {
+        return Ok(());
+    };
+
+    info!("Close match pick, searching for {search}...");
+
+    interaction
+        .create_response(
+            &ctx.http,
+            UpdateMessage(process_search(search, interaction.guild_id).into()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Add a missed search term as an alias for its best guess.
+async fn map_miss(interaction: &ComponentInteraction, ctx: &Context, values: &[String]) -> Res {
+    // the value is the set code and the search term
+    let Some((set, term)) = values.first().and_then(|v| v.split_once(':')) else {
+        return Ok(());
+    };
+
+    let guess = MISSES
+        .lock()
+        .unwrap()
+        .get(set)
+        .and_then(|m| m.get(term))
+        .and_then(|d| d.guess.clone());
+
+    let status = match guess.map(|g| register_alias(set, term, &g)) {
+        Some(Ok(name)) => {
+            done!("Mapped miss {} to {}", term.blue(), name.green());
+            format!("Added alias \"{term}\" for {name} in `{set}`.")
+        }
+        Some(Err(err)) => err,
+        None => format!("\"{term}\" is no longer a miss in `{set}`."),
+    };
+
+    let msg = misses_message();
+    let content = format!("{status}\n\n{}", msg.content);
+
+    interaction
+        .create_response(
+            &ctx.http,
+            UpdateMessage(MessageAdapter { content, ..msg }.into()),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -6,13 +6,13 @@ use std::{
     fs::File,
     hash::{DefaultHasher, Hash, Hasher},
     io::{Cursor, Read},
-    sync::Mutex,
+    sync::{LazyLock, Mutex},
 };
 
 use image::GenericImageView;
 use isahc::ReadResponseExt;
-use lazy_static::lazy_static;
 use magpie_engine::prelude::*;
+use magpie_engine::query::Separator;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -35,11 +35,17 @@ pub use traits::*;
 mod fuzzy;
 pub use fuzzy::*;
 
+mod alias;
+pub use alias::*;
+
+mod misses;
+pub use misses::*;
+
 #[macro_use]
 pub mod r#macro;
 
 use self::{
-    engine::{FilterExt, MagpieCosts, MagpieExt},
+    engine::{CostType, FilterExt, MagpieCosts, MagpieExt},
     fetch::AugBranch,
 };
 
@@ -93,116 +99,157 @@ pub struct CacheData {
 /// Location of the cache file.
 pub const CACHE_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "\\cache.bin");
 
-lazy_static! {
-    /// The regex use to match for general search.
-    pub static ref SEARCH_REGEX: Regex = Regex::new(r"(\S*)\[\[(.*?)\]\]") .unwrap_or_die("Cannot compiling search regex fails");
-    /// The regex use to match cache attachment link.
-    pub static ref CACHE_REGEX: Regex = Regex::new(r"(\d+)\/(\d+)\/(\d+)\.png\?ex=(\w+)") .unwrap_or_die("Cannot compiling cache regex fails");
-    /// The regex use to match message and tokenize them
-    pub static ref QUERY_REGEX: Regex = Regex::new(r#"(?:"([\w\s]+)")|([-\w]+)|([^\s\w"-]*)"#) .unwrap_or_die("Cannot compile query regex");
-    /// The regex use to match cost value in query
-    pub static ref COST_REGEX: Regex = Regex::new(r"(-?\d+)?([a-zA-Z])").unwrap_or_die("Cannot compile query regex");
-    /// The regex use to detech if a messagae asking for a game
-    pub static ref FIGHT_REGEX: Regex = Regex::new(r"wants? to (?:play|fight)").unwrap_or_die("Cannot compile asking for fight regex");
-
-    /// Collection of all set magpie use
-    pub static ref SETS: Mutex<HashMap<&'static str, Set>> = Mutex::new(load_set());
-
-    /// Debug card use to test rendering
-    pub static ref DEBUG_CARD: Card = Card {
-        set: SetCode::new("des").unwrap(),
-        name: "OLD_DATA".to_owned(),
-        description: "If you gaze long into an abyss, the abyss also gazes into you.".to_owned(),
-        portrait: "https://pbs.twimg.com/media/DUgfSnpU0AAA5Ky.jpg".to_owned(),
-
-        rarity: Rarity::RARE,
-        temple: Temple::ARTISTRY,
-        tribes: Some("Big Green Mother".to_string()),
-
-        attack: Attack::Num(420),
-        health: 10,
-        sigils: Vec::new(),
-        costs: Some(Costs {
-            blood: isize::MAX,
-            bone: isize::MIN,
-            energy: 100,
-            mox: Mox::all(),
-            mox_count: Some(MoxCount {
-                o:6,
-                g:9,
-                b:4,
-                y:2,
-                k:1,
+/// The regex use to match for general search.
+pub static SEARCH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\S*)\[\[(.*?)\]\]").unwrap_or_die("Cannot compiling search regex fails")
+});
+/// The regex use to match cache attachment link.
+pub static CACHE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d+)\/(\d+)\/(\d+)\.png\?ex=(\w+)")
+        .unwrap_or_die("Cannot compiling cache regex fails")
+});
+/// The query parser with Magpie's extra keywords.
+pub static QUERY_PARSER: LazyLock<QueryParser<MagpieExt, MagpieCosts, FilterExt>> =
+    LazyLock::new(|| {
+        QueryParser::new()
+            .keyword(&["name", "n"], Separator::Tilde, |v| {
+                Ok(FilterExt::Fuzzy(v.to_owned()))
+            })
+            .keyword(&["sigil", "s"], Separator::Tilde, |v| {
+                Ok(FilterExt::FuzzySigil(v.to_owned()))
+            })
+            .keyword(&["artist", "art"], Separator::Colon, |v| {
+                Ok(FilterExt::Field(FieldFilter::new("artist", v)))
+            })
+            .keyword(&["costtype", "ct"], Separator::Colon, |v| {
+                let mut t = CostType::empty();
+                for c in v.to_lowercase().chars() {
+                    t |= match c {
+                        'b' => CostType::BLOOD,
+                        'o' => CostType::BONE,
+                        'e' => CostType::ENERGY,
+                        'm' => CostType::MOX,
+                        _ => return Err(format!("invalid cost type `{v}`")),
+                    }
+                }
+                Ok(FilterExt::CostType(t))
+            })
+    });
+/// The regex use to detech if a messagae asking for a game
+pub static FIGHT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"wants? to (?:play|fight)").unwrap_or_die("Cannot compile asking for fight regex")
+});
+
+/// Collection of all set magpie use
+pub static SETS: LazyLock<Mutex<HashMap<&'static str, Set>>> =
+    LazyLock::new(|| Mutex::new(load_set()));
+
+/// Debug card use to test rendering
+pub static DEBUG_CARD: LazyLock<Card> = LazyLock::new(|| Card {
+    set: SetCode::new("des").unwrap(),
+    name: "OLD_DATA".to_owned(),
+    description: "If you gaze long into an abyss, the abyss also gazes into you.".to_owned(),
+    portrait: "https://pbs.twimg.com/media/DUgfSnpU0AAA5Ky.jpg".to_owned(),
+
+    rarity: Rarity::RARE,
+    temple: Temple::ARTISTRY,
+    tribes: Some("Big Green Mother".to_string()),
+
+    attack: Attack::Num(420),
+    health: 10,
+    sigils: Vec::new(),
+    costs: Some(Costs {
+        blood: isize::MAX,
+        bone: isize::MIN,
+        energy: 100,
+        mox: Mox::all(),
+        mox_count: Some(MoxCount {
+            o: 6,
+            g: 9,
+            b: 4,
+            y: 2,
+            k: 1,
+            r: 1,
+            e: 1,
+            p: 1,
+        }),
+        extra: MagpieCosts {
+            shattered_count: Some(MoxCount {
+                o: 1,
+                g: 9,
+                b: 8,
+                y: 4,
+                k: 1,
                 r: 1,
                 e: 1,
                 p: 1,
             }),
-            extra: MagpieCosts {
-                shattered_count: Some(MoxCount {
-                    o: 1,
-                    g: 9,
-                    b: 8,
-                    y: 4,
-                    k: 1,
-                    r: 1,
-                    e: 1,
-                    p: 1,
-                }),
-                max: 451,
-                link: 6,
-                gold: 24601
-            }
-        }),
-        traits: Some(Traits {
-            strings: Some(["Beastly", "Trait 13", "Prisoner 24601"].into_iter().map(std::string::ToString::to_string).collect()),
-            flags: TraitsFlag::all(),
-        }),
-        related: vec![
-            "Phi".to_owned(),
-            "NEW_DATA".to_owned(),
-            "ANCIENT_DATA".to_owned(),
-        ],
-        extra: MagpieExt {
-            artist: String::from("artist")
+            max: 451,
+            link: 6,
+            gold: 24601,
         },
-    };
-
-    /// Portrait Caches to save times on image processing
-    pub static ref CACHE: Mutex<HashMap<u64, CacheData>> = load_cache();
-
-    /// List of response that ping will return
-    pub static ref PING_RESPONSE: [&'static str;26] = [
-        "Crazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\n",
-        "I don't know who you are. I don't know what you want. If you are looking for ransom I can tell you I don't have money, but what I do have are a very particular set of skills. Skills I have acquired over a very long career. Skills that make me a nightmare for people like you. If you let my daughter go now that'll be the end of it. I will not look for you, I will not pursue you, but if you don't, I will look for you, I will find you and I will kill you.",
-        "I'm doing your mom at this very instance",
-        "Mike, If you are reading this, you've been in a coma for 5 years, we're trying a new technique, please, wake up.",
-        "New update in sometime",
-        "Something Something",
-        "Soon",
-        "Stoat is not dense >:(",
-        "We been trying to reach you about your car extended warranty",
-        "What did I miss",
-        "babe wake up the bot is online",
-        "https://www.youtube.com/watch?v=b7vWLz9iGsk",
-        "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
-        "ijo ijo",
-        "mi mute li lukin toki e ni. tomo tawa sina li tenpo suli awen.",
-        "mi olin musi Insison",
-        "mi olin toki pona",
-        "mi sona semi",
-        "mi unpa mama sini lon tenpo ni",
-        "o jan Mike. sina toki la sina lape suli lon luka tenpo sike. mi mute li lukin e sin nasin. o pini lape",
-        "o sewi sewi anpa anpa poka poka teje teje A B A B",
-        "sin ijo lon tenpo",
-        "sina sona ala sona toki pona",
-        "suwi olin mi a. ilo jan li lon aaa",
-        "tenpo kama",
-        "Pong!"
-    ];
-}
+    }),
+    traits: Some(Traits {
+        strings: Some(
+            ["Beastly", "Trait 13", "Prisoner 24601"]
+                .into_iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+        ),
+        flags: TraitsFlag::all(),
+    }),
+    related: vec![
+        "Phi".to_owned(),
+        "NEW_DATA".to_owned(),
+        "ANCIENT_DATA".to_owned(),
+    ],
+    extra: MagpieExt {
+        artist: String::from("artist"),
+    },
+});
+
+/// Portrait Caches to save times on image processing
+pub static CACHE: LazyLock<Mutex<HashMap<u64, CacheData>>> = LazyLock::new(load_cache);
+
+/// Aliases added by admin, they are save to the local alias file.
+pub static ALIASES: LazyLock<Mutex<AliasStore>> =
+    LazyLock::new(|| Mutex::new(alias::load_aliases()));
+
+/// Search terms that found nothing or only a poor match.
+pub static MISSES: LazyLock<Mutex<MissStore>> = LazyLock::new(|| Mutex::new(misses::load_misses()));
+
+/// List of response that ping will return
+pub static PING_RESPONSE: [&str; 26] = [
+    "Crazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\nCrazy?\nI was crazy once\nThey lock me in a room\nA rubber room\nA rubber room with rats\nThe rats make me crazy\n",
+    "I don't know who you are. I don't know what you want. If you are looking for ransom I can tell you I don't have money, but what I do have are a very particular set of skills. Skills I have acquired over a very long career. Skills that make me a nightmare for people like you. If you let my daughter go now that'll be the end of it. I will not look for you, I will not pursue you, but if you don't, I will look for you, I will find you and I will kill you.",
+    "I'm doing your mom at this very instance",
+    "Mike, If you are reading this, you've been in a coma for 5 years, we're trying a new technique, please, wake up.",
+    "New update in sometime",
+    "Something Something",
+    "Soon",
+    "Stoat is not dense >:(",
+    "We been trying to reach you about your car extended warranty",
+    "What did I miss",
+    "babe wake up the bot is online",
+    "https://www.youtube.com/watch?v=b7vWLz9iGsk",
+    "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+    "ijo ijo",
+    "mi mute li lukin toki e ni. tomo tawa sina li tenpo suli awen.",
+    "mi olin musi Insison",
+    "mi olin toki pona",
+    "mi sona semi",
+    "mi unpa mama sini lon tenpo ni",
+    "o jan Mike. sina toki la sina lape suli lon luka tenpo sike. mi mute li lukin e sin nasin. o pini lape",
+    "o sewi sewi anpa anpa poka poka teje teje A B A B",
+    "sin ijo lon tenpo",
+    "sina sona ala sona toki pona",
+    "suwi olin mi a. ilo jan li lon aaa",
+    "tenpo kama",
+    "Pong!"
+];
 
 fn load_set() -> HashMap<&'static str, Set> {
-    set_map! {
+    let mut sets = set_map! {
         standard (std) => "https://raw.githubusercontent.com/107zxz/inscr-onln-ruleset/main/standard.json",
         eternal (ete) => "https://raw.githubusercontent.com/EternalHours/EternalFormat/main/IMF_Eternal.json",
         egg (egg) => "https://raw.githubusercontent.com/senor-huevo/Mr.Egg-s-Goofy/main/Mr.Egg's%20Goofy.json",
@@ -211,7 +258,10 @@ fn load_set() -> HashMap<&'static str, Set> {
         aug_main (Aug) => fetch_aug_set(AugBranch::Main),
         descryption (des) => fetch_desc_set(),
         custom_tcg (cti) => fetch_cti_set(),
-    }
+    };
+
+    apply_aliases(&mut sets);
+    sets
 }
 
 fn load_cache() -> Mutex<HashMap<u64, CacheData>> {
@@ -3,7 +3,8 @@
 use std::panic::PanicHookInfo;
 
 use magpie_tutor::{
-    commands::*, done, error, frameworks, info, Color, Data, CACHE, CACHE_FILE_PATH, SETS,
+    autosave_misses, commands::*, done, error, frameworks, info, Color, Data, CACHE,
+    CACHE_FILE_PATH, SETS,
 };
 use poise::serenity_prelude::{CacheHttp, ClientBuilder, GatewayIntents, GuildId};
 
@@ -18,7 +19,7 @@ async fn main() {
 
     // poise framework
     let framework = frameworks! {
-        global: help(), show_modifiers(), ping(), query_help();
+        global: help(), show_modifiers(), ping(), query_help(), add_alias(), misses();
         guild (1199457939333849118): test();
         guild (994573431880286289): tunnel_status();
         ---
@@ -41,6 +42,9 @@ async fn main() {
 
     std::panic::set_hook(Box::new(panic_hook));
 
+    // misses are only record in memory while searching so save them from time to time
+    tokio::spawn(autosave_misses());
+
     // client time
     let client = ClientBuilder::new(token, intents)
         .framework(framework)
@@ -4,6 +4,8 @@ use poise::serenity_prelude::{
     InteractionResponseFlags, MessageFlags,
 };
 
+use poise::CreateReply;
+
 use crate::builder;
 
 builder! {
@@ -75,6 +77,29 @@ impl From<MessageAdapter> for EditInteractionResponse {
     }
 }
 
+impl From<MessageAdapter> for CreateReply {
+    fn from(
+        MessageAdapter {
+            content,
+            embeds,
+            attachments,
+            allowed_mentions,
+            components,
+            ephemeral,
+        }: MessageAdapter,
+    ) -> Self {
+        let mut reply = CreateReply::default()
+            .content(content)
+            .allowed_mentions(allowed_mentions)
+            .components(components)
+            .ephemeral(ephemeral);
+
+        reply.embeds = embeds;
+        reply.attachments = attachments;
+        reply
+    }
+}
+
 impl From<MessageAdapter> for CreateInteractionResponseMessage {
     fn from(
         MessageAdapter {
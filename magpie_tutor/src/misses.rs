@@ -0,0 +1,158 @@
+//! Record of search terms that found nothing or only a poor match.
+//!
+//! Admin can look at the most common misses and turn them into aliases so the next search find
+//! the right card.
+
+use std::{
+    collections::HashMap,
+    fs,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use magpie_engine::query::normalize_name;
+use serde::{Deserialize, Serialize};
+
+use crate::{done, error, Color, MISSES};
+
+/// Type alias for the local miss store, map set code to normalized search term to the miss data.
+pub type MissStore = HashMap<String, HashMap<String, MissData>>;
+
+/// Location of the local miss file.
+pub const MISS_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "\\misses.json");
+
+/// How often the misses are save to the miss file if there are new one.
+pub const MISS_SAVE_INTERVAL: Duration = Duration::from_mins(5);
+
+/// Set when a miss is record and not save yet.
+static UNSAVED: AtomicBool = AtomicBool::new(false);
+
+/// Search with a best match ranking lower than this are record as a miss.
+pub const LOW_CONFIDENCE: f32 = 0.7;
+
+/// Data about a missed search term.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MissData {
+    /// How many time the term was search.
+    pub count: u64,
+    /// The name of the closest card found the last time the term was search.
+    pub guess: Option<String>,
+}
+
+/// A missed search term, see [`top_misses`].
+#[derive(Debug, Clone)]
+pub struct Miss {
+    /// The set code the term was search in.
+    pub set: String,
+    /// The normalized search term.
+    pub term: String,
+    /// The miss data.
+    pub data: MissData,
+}
+
+/// Load the misses from the local miss file.
+pub(crate) fn load_misses() -> MissStore {
+    let Ok(content) = fs::read_to_string(MISS_FILE_PATH) else {
+        return MissStore::new();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        error!("Cannot parse miss file: {err}");
+        MissStore::new()
+    })
+}
+
+/// Save the misses to the miss file.
+pub fn save_misses() {
+    let content = {
+        let misses = MISSES.lock().unwrap();
+        UNSAVED.store(false, Ordering::Relaxed);
+        serde_json::to_string_pretty(&*misses).unwrap()
+    };
+    fs::write(MISS_FILE_PATH, content).expect("Cannot write miss file");
+    done!("Misses save successfully to {}", MISS_FILE_PATH.green());
+}
+
+/// Save the misses every [`MISS_SAVE_INTERVAL`] if new misses were record since the last save.
+///
+/// This is meant to be spawn once when the bot start.
+pub async fn autosave_misses() {
+    let mut interval = tokio::time::interval(MISS_SAVE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        if UNSAVED.load(Ordering::Relaxed) {
+            // writing the file is blocking so don't do it on the runtime threads
+            if let Err(err) = tokio::task::spawn_blocking(save_misses).await {
+                error!("Cannot save misses: {err}");
+            }
+        }
+    }
+}
+
+/// Record a search term that found nothing or only a poor match along with the closest card.
+///
+/// This is call while searching so it only update the misses in memory, they are save later by
+/// [`autosave_misses`].
+pub fn record_miss(set_code: &str, term: &str, guess: Option<&str>) {
+    let term = normalize_name(term);
+    if term.is_empty() {
+        return;
+    }
+
+    let mut misses = MISSES.lock().unwrap();
+    let data = misses
+        .entry(set_code.to_owned())
+        .or_default()
+        .entry(term)
+        .or_default();
+
+    data.count += 1;
+    if let Some(guess) = guess {
+        data.guess = Some(guess.to_owned());
+    }
+
+    UNSAVED.store(true, Ordering::Relaxed);
+}
+
+/// Remove a search term from the misses, return true if it was record.
+pub fn clear_miss(set_code: &str, term: &str) -> bool {
+    let removed = MISSES
+        .lock()
+        .unwrap()
+        .get_mut(set_code)
+        .and_then(|m| m.remove(&normalize_name(term)))
+        .is_some();
+
+    if removed {
+        save_misses();
+    }
+
+    removed
+}
+
+/// Get the `n` most common misses across all sets.
+pub fn top_misses(n: usize) -> Vec<Miss> {
+    let mut misses: Vec<Miss> = MISSES
+        .lock()
+        .unwrap()
+        .iter()
+        .flat_map(|(set, m)| {
+            m.iter().map(|(term, data)| Miss {
+                set: set.clone(),
+                term: term.clone(),
+                data: data.clone(),
+            })
+        })
+        .collect();
+
+    // sort by name too so the order is the same every time
+    misses.sort_by(|a, b| {
+        b.data
+            .count
+            .cmp(&a.data.count)
+            .then_with(|| (&a.set, &a.term).cmp(&(&b.set, &b.term)))
+    });
+    misses.truncate(n);
+    misses
+}
@@ -1,55 +1,66 @@
 //! Contain the main querying function and implementations.
 //!
-//! The query take in a input pass it into a simple lexer and then into a simple parser to get a
-//! list of keywords. These keywords then get converted into a set of filters to then be use for
-//! [`QueryBuilder`]
+//! The query is parse by the engine [`QueryParser`] with a few extra keywords for Magpie's
+//! [`FilterExt`](crate::engine::FilterExt). The filters are then use for [`QueryBuilder`]
 
-use std::vec;
+use std::fmt::Display;
 
 use magpie_engine::prelude::*;
+use magpie_engine::query::group_by_name;
 use poise::serenity_prelude::{colours::roles, CreateEmbed};
 
 use crate::emojis::cost;
-use crate::{Filters, Set};
+use crate::{Filters, Set, QUERY_PARSER};
 
-pub mod lexer;
-pub mod parser;
-
-use lexer::tokenize_query;
-
-use self::parser::QueryParser;
-
-macro_rules! unwrap {
-    ($expr:expr) => {
-        match $expr {
-            Ok(it) => it,
-            Err(err) => {
-                return CreateEmbed::new()
-                    .color(roles::RED)
-                    .title("Query Error")
-                    .description(err)
-            }
+/// Query a message
+///
+/// If the query filter for specific sets using the `set` keyword, every set in `all_sets` is
+/// query instead of the selected `sets`. When more than one set is query the same card from
+/// different sets are group together like `Stoat (std, ete; egg)`, with `;` between the
+/// different versions of the card.
+pub fn query_message<'a>(sets: Vec<&'a Set>, all_sets: Vec<&'a Set>, query: &str) -> CreateEmbed {
+    let filters: Vec<Filters> = match QUERY_PARSER.parse(query) {
+        Ok(it) => it,
+        Err(err) => {
+            return CreateEmbed::new()
+                .color(roles::RED)
+                .title("Query Error")
+                .description(format!("```\n{}\n```\n{err}", err.underline(query)))
         }
     };
-}
 
-/// Query a message
-pub fn query_message(sets: Vec<&Set>, query: &str) -> CreateEmbed {
-    let tokens = unwrap!(tokenize_query(query));
-    let keywords = unwrap!(QueryParser::gen_ast_with(tokens));
-
-    let mut filters: Vec<Filters> = vec![];
-
-    for kw in keywords {
-        filters.push(unwrap!(kw.try_into()));
-    }
+    let sets = if filters.iter().any(has_set_filter) {
+        all_sets
+    } else {
+        sets
+    };
 
+    let multi_set = sets.len() > 1;
     let query = QueryBuilder::with_filters(sets, filters).query();
+    let breakdown = facets_breakdown(&query.facets());
 
-    let output = query
-        .cards
+    let output = group_by_name(query.cards.iter().copied())
         .iter()
-        .map(|c| c.name.as_str())
+        .map(|g| {
+            let name = &g.versions[0].card.name;
+            if !multi_set {
+                return name.clone();
+            }
+
+            let versions = g
+                .versions
+                .iter()
+                .map(|v| {
+                    v.sets
+                        .iter()
+                        .map(SetCode::code)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!("{name} ({versions})")
+        })
         .collect::<Vec<_>>()
         .join(", ");
 
@@ -60,7 +71,7 @@ pub fn query_message(sets: Vec<&Set>, query: &str) -> CreateEmbed {
             query.cards.len()
         ))
         .description(if query.cards.len() >= 200 || output.len() >= 2000 {
-            String::from("Too many results...Try narrowing your search")
+            format!("{breakdown}Too many results...Try narrowing your search")
         } else {
             let string_filters = query
                 .filters
@@ -77,6 +88,45 @@ pub fn query_message(sets: Vec<&Set>, query: &str) -> CreateEmbed {
                 .replace(" green", cost::GREEN)
                 .replace(" blue", cost::BLUE);
 
-            format!("Cards that {string_filters}\n{output}")
+            format!("Cards that {string_filters}\n{breakdown}{output}")
+        })
+}
+
+/// Short breakdown of the most common temples, rarities and cost types in the result like
+/// `12 beast, 4 undead; 10 common, 6 rare`. Return an empty string if there are no result.
+fn facets_breakdown(facets: &Facets) -> String {
+    fn top<T: Display>(counts: &[(T, usize)]) -> Option<String> {
+        (!counts.is_empty()).then(|| {
+            counts
+                .iter()
+                .take(3)
+                .map(|(v, n)| format!("{n} {v}"))
+                .collect::<Vec<_>>()
+                .join(", ")
         })
+    }
+
+    let groups = [
+        top(&facets.temple),
+        top(&facets.rarity),
+        top(&facets.cost_type),
+    ];
+
+    let breakdown = groups.into_iter().flatten().collect::<Vec<_>>().join("; ");
+
+    if breakdown.is_empty() {
+        breakdown
+    } else {
+        format!("-# {breakdown}\n")
+    }
+}
+
+/// Check if a filter or any of its inner filters is a set filter.
+fn has_set_filter(filter: &Filters) -> bool {
+    match filter {
+        Filters::Set(_) => true,
+        Filters::And(a, b) | Filters::Or(a, b) => has_set_filter(a) || has_set_filter(b),
+        Filters::Not(f) | Filters::RelatedOf(f) => has_set_filter(f),
+        _ => false,
+    }
 }
@@ -6,14 +6,17 @@ use poise::serenity_prelude::{
     colours::roles,
     ButtonStyle::{Danger, Primary},
     Context,
-    CreateActionRow::Buttons,
-    CreateAttachment, CreateButton, CreateEmbed, CreateMessage, GuildId, Message,
+    CreateActionRow::{self, Buttons},
+    CreateAttachment, CreateButton, CreateEmbed, CreateMessage, CreateSelectMenu,
+    CreateSelectMenuKind, CreateSelectMenuOption, GuildId, Message,
 };
 
+use magpie_engine::query::normalize_name;
+
 use crate::{
-    current_epoch, done, fuzzy_best, hash_card_url, info, query::query_message, save_cache,
-    CacheData, Card, Color, Death, FuzzyRes, MessageAdapter, MessageCreateExt, Res, CACHE,
-    CACHE_REGEX, DEBUG_CARD, SEARCH_REGEX, SETS,
+    current_epoch, did_you_mean, done, fuzzy_cards, hash_card_url, info, query::query_message,
+    record_miss, save_cache, CacheData, Card, Color, Death, FuzzyRes, MessageAdapter,
+    MessageCreateExt, Res, CACHE, CACHE_REGEX, DEBUG_CARD, LOW_CONFIDENCE, SEARCH_REGEX, SETS,
 };
 mod portrait;
 #[allow(clippy::wildcard_imports)]
@@ -40,7 +43,7 @@ bitflags! {
 }
 
 /// Main searching function.
-pub async fn search_message(ctx: &Context, msg: &Message, guild_id: GuildId) -> Res {
+pub async fn search_message(ctx: &Context, msg: &Message, guild_id: Option<GuildId>) -> Res {
     if !SEARCH_REGEX.is_match(&msg.content) {
         return Ok(());
     }
@@ -63,12 +66,19 @@ pub async fn search_message(ctx: &Context, msg: &Message, guild_id: GuildId) ->
     Ok(())
 }
 
-/// Process a search with a content and return the message to send
-pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
+/// Process a search with a content and return the message to send.
+///
+/// The guild is use to pick the default set, search outside of a guild like in DM use the
+/// standard set.
+pub fn process_search(content: &str, guild_id: Option<GuildId>) -> MessageAdapter {
     let start = Instant::now();
 
     let mut embeds = vec![];
     let mut attachments: Vec<CreateAttachment> = vec![];
+    // close match from every set when nothing is found in all set mode
+    let mut suggestions: Vec<(f32, String)> = vec![];
+    // select menus to pick between close matches
+    let mut menus: Vec<CreateActionRow> = vec![];
 
     let g_sets = SETS.lock().unwrap();
 
@@ -78,27 +88,18 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
             c.get(2).map_or("", |s| s.as_str()),
         )
     }) {
-        let (set_code, modifier): (Vec<&str>, &str) = 'a: {
-            // Just leave if we don;t have anything to process
-            if modifier.is_empty() {
-                break 'a (vec![], "");
-            }
-
+        let (set_code, modifier): (Vec<&str>, &str) = {
             let mut set = vec![]; // no allocation so it fine
             let mut i = modifier.len(); // get the length for slicing
 
-            // if we can't split any set code quit
-            if i < 3 {
-                break 'a (vec![], modifier);
-            }
-
-            // split the modifier from the back to detech set code
-            while let Some(code) = modifier.get((i - 3)..i) {
-                set.push(code);
-                i -= 3;
-                if i < 3 {
+            // split the modifier from the back to detech set code, only split off code of set we
+            // actually have so modifier don't get mistaken as set code
+            while let Some(code) = i.checked_sub(3).and_then(|s| modifier.get(s..i)) {
+                if !g_sets.contains_key(code) {
                     break;
                 }
+                set.push(code);
+                i -= 3;
             }
 
             (set, &modifier[..i])
@@ -144,58 +145,103 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
             }
         }
 
+        let default_set = match guild_id.map(GuildId::get) {
+            // Default to aug in the augmented server
+            Some(1028530290727063604) => "aug",
+            // Default to des in the descryption server
+            Some(1257552767984074803) => "des",
+
+            _ => "std",
+        };
+
         if sets.is_empty() {
-            sets.push(
-                g_sets
-                    .get(match guild_id.get() {
-                        // Default to aug in the augmented server
-                        1028530290727063604 => "aug",
-                        // Default to des in the descryption server
-                        1257552767984074803 => "des",
-
-                        _ => "std",
-                    })
-                    .unwrap(),
-            );
+            sets.push(g_sets.get(default_set).unwrap());
         }
 
         if modifier.contains(Modifier::QUERY) {
-            embeds.push(query_message(sets, search_term));
+            embeds.push(query_message(sets, g_sets.values().collect(), search_term));
+            continue;
+        }
+
+        // sigil search merge the result from every set into one embed
+        if modifier.contains(Modifier::SIGIL) {
+            info!("Searching sigil...");
+            embeds.push(sigil_search(&sets, search_term));
+            done!("Sigil search complete");
             continue;
         }
 
+        // all set search only record a miss when no set found the card, use the best guess
+        // across every set
+        let set_count = sets.len();
+        let mut missed = 0;
+        let mut best_guess: Option<(f32, &str, String)> = None;
+
         for set in sets {
-            if modifier.contains(Modifier::SIGIL) {
-                info!("Searching sigil...");
-                embeds.push(sigil_search(set, search_term));
-                done!("Sigil search complete");
-                continue;
-            }
             let FuzzyRes { rank, data: card } = if search_term == "old_data" {
                 FuzzyRes {
                     rank: 4.2,
                     data: &*DEBUG_CARD,
                 }
-            } else if let Some(best) = {
-                info!("Fuzzy searching for: {}", search_term);
-                let res = fuzzy_best(search_term, set.cards.iter().collect(), 0.5, |c: &Card| {
-                    c.name.as_str()
-                });
-                res
-            } {
-                done!("Fuzzy search succeed found: {}", best.data.name);
-                best
             } else {
-                done!("{}", "No results from fuzzy search!".red());
-                if !modifier.contains(Modifier::ALL_SET) {
-                    embeds.push({
-                        CreateEmbed::new()
-                            .color(roles::RED)
-                            .title(format!("Card \"{search_term}\" not found in selected set ({})", set.name))
-                            .description( "No card found with sufficient similarity with the search term in the selected set.")
-                    });
+                info!("Fuzzy searching for: {}", search_term);
+                let mut res = fuzzy_cards(set, search_term, 0.25, 5);
+
+                if res.first().is_some_and(|r| r.rank >= 0.5) {
+                    let close = close_matches(search_term, &res);
+                    if close.len() > 1 && menus.len() < MAX_MENUS {
+                        info!("Found {} close matches adding a select menu", close.len());
+                        menus.push(pick_menu(
+                            menus.len(),
+                            search_term,
+                            &close,
+                            modifier.contains(Modifier::COMPACT),
+                        ));
+                    }
+
+                    let best = res.swap_remove(0);
+                    done!("Fuzzy search succeed found: {}", best.data.name);
+
+                    // all set search miss in most set so only record normal search
+                    if best.rank < LOW_CONFIDENCE && !modifier.contains(Modifier::ALL_SET) {
+                        info!("Low confidence match recording as a miss...");
+                        record_miss(set.code.code(), search_term, Some(&best.data.name));
+                    }
+
+                    best
+                } else {
+                    done!("{}", "No results from fuzzy search!".red());
+                    let close: Vec<_> = res
+                        .iter()
+                        .take(3)
+                        .map(|r| (r.rank, r.data.name.clone()))
+                        .collect();
+
+                    if modifier.contains(Modifier::ALL_SET) {
+                        missed += 1;
+                        if let Some((rank, name)) = close.first() {
+                            if best_guess.as_ref().is_none_or(|(r, _, _)| rank > r) {
+                                best_guess = Some((*rank, set.code.code(), name.clone()));
+                            }
+                        }
+                        suggestions.extend(close);
+                    } else {
+                        record_miss(
+                            set.code.code(),
+                            search_term,
+                            close.first().map(|(_, n)| n.as_str()),
+                        );
+
+                        let names: Vec<_> = close.into_iter().map(|(_, n)| n).collect();
+                        embeds.push({
+                            CreateEmbed::new()
+                                .color(roles::RED)
+                                .title(format!("Card \"{search_term}\" not found in selected set ({})", set.name))
+                                .description(did_you_mean(&names).unwrap_or_else(|| "No card found with sufficient similarity with the search term in the selected set.".to_owned()))
+                        });
+                    }
+                    continue;
                 }
-                continue;
             };
 
             if modifier.contains(Modifier::DEBUG) {
@@ -213,7 +259,7 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
                 card,
                 g_sets.get(card.set.code()).unwrap(),
                 modifier.contains(Modifier::COMPACT),
-                unused_mod.clone(),
+                &unused_mod,
             );
             info!("Embed for {} generated", search_term);
             info!("Grabbing portrait for {}", search_term);
@@ -250,12 +296,21 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
 
             embeds.push(embed);
         }
+
+        if modifier.contains(Modifier::ALL_SET) && missed == set_count {
+            info!("No set found the card recording as a miss...");
+            match &best_guess {
+                Some((_, code, name)) => record_miss(code, search_term, Some(name)),
+                None => record_miss(default_set, search_term, None),
+            }
+        }
     }
 
     done!("Finished all search sending message...");
 
     if embeds.len() > 10 {
         embeds.clear();
+        menus.clear();
         embeds.push(
             CreateEmbed::new()
                 .title("Too many embeds")
@@ -263,10 +318,18 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
                 .color(roles::RED)
         );
     } else if embeds.is_empty() {
+        suggestions.sort_by(|(r1, n1), (r2, n2)| r2.total_cmp(r1).then_with(|| n1.cmp(n2)));
+        let mut names: Vec<String> = vec![];
+        for (_, name) in suggestions {
+            if names.len() < 3 && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
         embeds.push(
             CreateEmbed::new()
                 .title("No card found in all selected set(s)")
-                .description("No card found with sufficient similarity with the search term in the selected set(s).")
+                .description(did_you_mean(&names).unwrap_or_else(|| "No card found with sufficient similarity with the search term in the selected set(s).".to_owned()))
         );
     }
 
@@ -275,12 +338,92 @@ pub fn process_search(content: &str, guild_id: GuildId) -> MessageAdapter {
         .content(format!("Search completed in {:.1?}", start.elapsed()))
         .embeds(embeds)
         .attachments(attachments)
-        .components(vec![Buttons(vec![
-            CreateButton::new("retry").style(Primary).label("Retry"),
-            CreateButton::new("remove_cache")
-                .style(Danger)
-                .label("Remove Cache"),
-        ])])
+        .components({
+            menus.push(Buttons(vec![
+                CreateButton::new("retry").style(Primary).label("Retry"),
+                CreateButton::new("remove_cache")
+                    .style(Danger)
+                    .label("Remove Cache"),
+            ]));
+            menus
+        })
+}
+
+/// How close the rank of another match have to be to the best match for it to be consider
+/// close.
+const CLOSE_MARGIN: f32 = 0.05;
+
+/// Discord only allow 5 action rows and one is use for the buttons.
+const MAX_MENUS: usize = 4;
+
+/// Get the matches close enough to the best match that the user might have meant them instead.
+///
+/// A match is close if it rank within [`CLOSE_MARGIN`] of the best match or if its name contain
+/// every word of the search term, like "Wolf Cub" and "Dire Wolf" for "wolf". Exact match are
+/// never ambiguous.
+fn close_matches<'a>(search_term: &str, res: &[FuzzyRes<'a, Card>]) -> Vec<&'a Card> {
+    let Some(best) = res.first() else {
+        return vec![];
+    };
+
+    if best.rank >= 1. {
+        return vec![];
+    }
+
+    let term = normalize_name(search_term);
+    let mut close: Vec<&Card> = res
+        .iter()
+        .filter(|r| {
+            let name = normalize_name(&r.data.name);
+            best.rank - r.rank <= CLOSE_MARGIN
+                || term
+                    .split_whitespace()
+                    .all(|w| name.split_whitespace().any(|n| n == w))
+        })
+        .map(|r| r.data)
+        .collect();
+
+    // option in a select menu must be unique, same name have the same rank so they are next to
+    // each other
+    close.dedup_by(|a, b| a.name == b.name);
+    close
+}
+
+/// Create a select menu to pick between close matches.
+///
+/// The value of each option is the search to run when it is pick so the handler doesn't have to
+/// know anything about the original search.
+fn pick_menu(i: usize, search_term: &str, cards: &[&Card], compact: bool) -> CreateActionRow {
+    let options = cards
+        .iter()
+        .map(|c| {
+            CreateSelectMenuOption::new(
+                &c.name,
+                format!(
+                    "{}{}[[{}]]",
+                    if compact { "c" } else { "" },
+                    c.set.code(),
+                    c.name
+                ),
+            )
+            .description(format!("From {}", c.set.code()))
+        })
+        .collect();
+
+    CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(
+            format!("pick_card_{i}"),
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder(truncate(&format!(
+            "Did you mean another card for \"{search_term}\"?"
+        ))),
+    )
+}
+
+/// Select menu placeholder can only be 150 characters long.
+fn truncate(text: &str) -> String {
+    text.chars().take(150).collect()
 }
 
 /// Uodate the cache with the messagge attachment
@@ -329,7 +472,7 @@ fn update_cache(msg: &Message) {
                 filename.blue()
             );
             new_cache += 1;
-        };
+        }
     }
 
     if new_cache > 0 {
@@ -27,7 +27,7 @@ pub fn gen_embed(
     card: &Card,
     set: &Set,
     compact: bool,
-    unused_mod: String,
+    unused_mod: &str,
 ) -> CreateEmbed {
     // The specific gen embed function should return the embed and the footer that they would like
     // to add.
@@ -1,3 +1,5 @@
+use std::fmt::Write;
+
 use magpie_engine::prelude::*;
 use poise::serenity_prelude::{colours::roles, CreateEmbed};
 
@@ -39,15 +41,16 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
         format!("*{}*\n\n", card.description)
     };
 
-    desc.push_str(&format!(
-        "**Tier:** {}\n",
+    let _ = writeln!(
+        desc,
+        "**Tier:** {}",
         match &card.rarity {
             Rarity::UNIQUE => String::from("talking"),
             a => a.to_string(),
         }
-    ));
+    );
     if let Some(t) = &card.tribes {
-        desc.push_str(&format!("**Tribes:** {t}\n"));
+        let _ = writeln!(desc, "**Tribes:** {t}");
     }
 
     desc.push('\n'); // cost separator
@@ -108,7 +111,8 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
 
     // remove this once we actually parse aug spatk
     #[allow(clippy::match_wildcard_for_single_variants)]
-    desc.push_str(&format!(
+    let _ = write!(
+        desc,
         "**Stat:** {} / {}",
         match &card.attack {
             Attack::Num(a) => a.to_string(),
@@ -116,17 +120,17 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
             _ => unreachable!(),
         },
         card.health
-    ));
+    );
 
     if !card.sigils.is_empty() {
         if compact {
-            desc.push_str(&format!("**Sigils:** {}\n", card.sigils.join(", ")));
+            let _ = writeln!(desc, "**Sigils:** {}", card.sigils.join(", "));
         } else {
             let mut desc = String::with_capacity(card.sigils.iter().map(String::len).sum());
 
             for s in &card.sigils {
-                let text = set.sigils_description.get(s).unwrap();
-                desc.push_str(&format!("**{s}:** {text}\n"));
+                let text = &set.sigil(s).unwrap().description;
+                let _ = writeln!(desc, "**{s}:** {text}");
             }
 
             embed = embed.field("== SIGILS ==", desc, false);
@@ -138,13 +142,16 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
     }) = &card.traits
     {
         if compact {
-            desc.push_str(&format!("**Traits:** {}", t.join(", ")));
+            let _ = write!(desc, "**Traits:** {}", t.join(", "));
         } else {
             let mut desc = String::with_capacity(t.iter().map(String::len).sum());
 
             for s in t {
-                let text = set.sigils_description.get(s).unwrap();
-                desc.push_str(&format!("**{s}:** {text}\n"));
+                // not every trait have a description
+                let _ = match set.trait_description(s) {
+                    Some(text) => writeln!(desc, "**{s}:** {text}"),
+                    None => writeln!(desc, "**{s}**"),
+                };
             }
 
             embed = embed.field("== TRAITS ==", desc, false);
@@ -1,5 +1,7 @@
 #![allow(unused)] // shush im fixing them
 
+use std::fmt::Write;
+
 use magpie_engine::prelude::*;
 use poise::serenity_prelude::{colours::roles, Colour, CreateEmbed};
 
@@ -40,9 +42,9 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
         format!("*{}*\n\n", card.description)
     };
 
-    desc.push_str(&format!("**Rarity:** {}\n", &card.rarity.to_string()));
+    let _ = writeln!(desc, "**Rarity:** {}", &card.rarity.to_string());
     if let Some(t) = &card.tribes {
-        desc.push_str(&format!("**Tribes:** {t}\n"));
+        let _ = writeln!(desc, "**Tribes:** {t}");
     }
 
     desc.push('\n'); // cost separator
@@ -83,7 +85,8 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
 
     // remove this once we actually parse aug spatk
     #[allow(clippy::match_wildcard_for_single_variants)]
-    desc.push_str(&format!(
+    let _ = write!(
+        desc,
         "**Stat:** {} / {}",
         match &card.attack {
             Attack::Num(a) => a.to_string(),
@@ -91,17 +94,17 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
             _ => unimplemented!(),
         },
         card.health
-    ));
+    );
 
     if !card.sigils.is_empty() {
         if compact {
-            desc.push_str(&format!("**Sigils:** {}\n", card.sigils.join(", ")));
+            let _ = writeln!(desc, "**Sigils:** {}", card.sigils.join(", "));
         } else {
             let mut desc = String::with_capacity(card.sigils.iter().map(String::len).sum());
 
             for s in &card.sigils {
-                let text = set.sigils_description.get(s).unwrap();
-                desc.push_str(&format!("**{s}:** {text}\n"));
+                let text = &set.sigil(s).unwrap().description;
+                let _ = writeln!(desc, "**{s}:** {text}");
             }
 
             embed = embed.field("== SIGILS ==", desc, false);
@@ -112,11 +115,20 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
         strings: Some(t), ..
     }) = &card.traits
     {
-        embed = embed.field(
-            "== TRAITS ==",
-            format!("**Traits:** {}", t.join(", ")),
-            false,
-        );
+        // only list the traits if none of them have a description
+        let value = if compact || t.iter().all(|s| set.trait_description(s).is_none()) {
+            format!("**Traits:** {}", t.join(", "))
+        } else {
+            t.iter()
+                .map(|s| match set.trait_description(s) {
+                    Some(text) => format!("**{s}:** {text}"),
+                    None => format!("**{s}**"),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        embed = embed.field("== TRAITS ==", value, false);
     }
 
     if compact {
@@ -1,3 +1,5 @@
+use std::fmt::Write;
+
 use magpie_engine::prelude::*;
 use poise::serenity_prelude::{colours::roles, CreateEmbed};
 
@@ -64,25 +66,26 @@ pub fn gen_embed(card: &Card, set: &Set, compact: bool) -> EmbedRes {
 
     // imf shouldn't have any other thing
     #[allow(clippy::match_wildcard_for_single_variants)]
-    desc.push_str(&format!(
-        "**Stat:** {} / {}\n",
+    let _ = writeln!(
+        desc,
+        "**Stat:** {} / {}",
         match &card.attack {
             Attack::Num(a) => a.to_string(),
             Attack::SpAtk(sp) => sp.to_emoji(),
             _ => unreachable!(),
         },
         card.health
-    ));
+    );
 
     if !card.sigils.is_empty() {
         if compact {
-            desc.push_str(&format!("**Sigils:** {}\n", card.sigils.join(", ")));
+            let _ = writeln!(desc, "**Sigils:** {}", card.sigils.join(", "));
         } else {
             let mut desc = String::with_capacity(card.sigils.iter().map(String::len).sum());
 
             for s in &card.sigils {
-                let text = set.sigils_description.get(s).unwrap();
-                desc.push_str(&format!("**{s}:** {text}\n"));
+                let text = &set.sigil(s).unwrap().description;
+                let _ = writeln!(desc, "**{s}:** {text}");
             }
 
             embed = embed.field("== SIGILS ==", desc, false);
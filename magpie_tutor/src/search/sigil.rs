@@ -1,30 +1,145 @@
 use poise::serenity_prelude::colours::roles;
 use poise::serenity_prelude::{CreateEmbed, CreateEmbedFooter};
 
-use crate::{fuzzy_best, FuzzyRes, Set};
+use magpie_engine::query::normalize_name;
 
-/// Search for a sigil using a set and a name
-pub fn sigil_search(set: &Set, sigil_name: &str) -> CreateEmbed {
-    let Some(FuzzyRes { rank, data: name }) = fuzzy_best(
+use magpie_engine::Sigil;
+
+use crate::{did_you_mean, fuzzy_top, FuzzyRes, Set};
+
+/// Discord limit the total length of an embed to 6000 characters so the card lists have to
+/// share this much.
+const CARD_LIST_BUDGET: usize = 3500;
+
+/// Discord limit a field value to 1024 characters.
+const FIELD_LIMIT: usize = 1000;
+
+/// Search for a sigil by name in the selected sets.
+///
+/// The matches from every set are merge into one embed that show the sigil text, grouping sets
+/// that have the same text together, and the cards carrying the sigil in each set.
+pub fn sigil_search(sets: &[&Set], sigil_name: &str) -> CreateEmbed {
+    let mut sets = sets.to_vec();
+    // all set search come from a map so sort them to keep the embed the same every time
+    sets.sort_by_key(|s| s.code.code());
+
+    // the same sigil in different set is only one candidate
+    let mut names: Vec<(String, &String)> = vec![];
+    for set in &sets {
+        for name in set.sigils.keys() {
+            let key = normalize_name(name);
+            if !names.iter().any(|(k, _)| *k == key) {
+                names.push((key, name));
+            }
+        }
+    }
+
+    let mut res = fuzzy_top(
         sigil_name,
-        set.sigils_description.keys().collect(),
-        0.5,
-        |s: &String| s.as_str(),
-    ) else {
+        names.iter().map(|(k, name)| (k.as_str(), *name)),
+        0.25,
+        3,
+    );
+
+    if !res.first().is_some_and(|r| r.rank >= 0.5) {
+        let names: Vec<_> = res.iter().map(|r| r.data).collect();
         return CreateEmbed::new()
             .color(roles::RED)
             .title(format!("Sigil \"{sigil_name}\" not found"))
-            .description(
-                "No sigil found with sufficient similarity with the search term in the selected set(s).",
-            );
+            .description(did_you_mean(&names).unwrap_or_else(|| {
+                "No sigil found with sufficient similarity with the search term in the selected set(s).".to_owned()
+            }));
+    }
+
+    let FuzzyRes { rank, data: name } = res.swap_remove(0);
+    let key = normalize_name(name);
+
+    // the different text of the sigil and the sets that use them
+    let mut texts: Vec<(&str, Vec<&str>)> = vec![];
+    let mut cards: Vec<(&str, Vec<&str>)> = vec![];
+    let mut found: Vec<&Sigil> = vec![];
+
+    for set in &sets {
+        let Some(sigil) = set
+            .sigils
+            .iter()
+            .find_map(|(s, sigil)| (normalize_name(s) == key).then_some(sigil))
+        else {
+            continue;
+        };
+        found.push(sigil);
+
+        let text = &sigil.description;
+        match texts.iter_mut().find(|(t, _)| t == text) {
+            Some((_, codes)) => codes.push(set.code.code()),
+            None => texts.push((text, vec![set.code.code()])),
+        }
+
+        let carriers: Vec<&str> = set
+            .cards
+            .iter()
+            .filter(|c| c.sigils.iter().any(|s| normalize_name(s) == key))
+            .map(|c| c.name.as_str())
+            .collect();
+
+        if !carriers.is_empty() {
+            cards.push((&set.name, carriers));
+        }
+    }
+
+    let description = match texts.as_slice() {
+        [(text, _)] => (*text).to_owned(),
+        _ => texts
+            .iter()
+            .map(|(text, codes)| format!("**{}:** {text}", codes.join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n"),
     };
 
-    CreateEmbed::new()
+    let limit = (CARD_LIST_BUDGET / cards.len().max(1)).min(FIELD_LIMIT);
+
+    // sets can disagree on the category so just use the first one
+    let category = found
+        .first()
+        .map_or_else(String::new, |s| format!("{} sigil\n", s.category));
+
+    let mut embed = CreateEmbed::new()
         .color(roles::TEAL)
         .title(name)
-        .description(set.sigils_description.get(name).unwrap())
+        .description(description)
+        .fields(
+            cards
+                .iter()
+                .map(|(set, names)| (format!("Cards in {set}"), card_list(names, limit), false)),
+        )
         .footer(CreateEmbedFooter::new(format!(
-            "Match {:.2}% with the search term",
+            "{category}Match {:.2}% with the search term",
             rank * 100.
-        )))
+        )));
+
+    if let Some(icon) = found.iter().find_map(|s| s.icon.as_ref()) {
+        embed = embed.thumbnail(icon);
+    }
+
+    embed
+}
+
+/// Join card names into a list no longer than the limit, cutting off with how many are left.
+fn card_list(names: &[&str], limit: usize) -> String {
+    let mut out = String::new();
+
+    for (i, name) in names.iter().enumerate() {
+        let sep = if out.is_empty() { "" } else { ", " };
+        let rest = format!("and {} more", names.len() - i);
+
+        out.push_str(sep);
+        if out.len() + name.len() + sep.len() + rest.len() > limit {
+            out.push_str(&rest);
+            break;
+        }
+
+        out.push_str(name);
+    }
+
+    out
 }